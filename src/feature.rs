@@ -1,15 +1,16 @@
-use crate::state::State;
-use crossterm::{event::KeyCode, style::StyledContent};
+use crate::{state::State, util::text::Line};
+use crossterm::event::KeyCode;
 
 /// Feature struct
-/// A struct that contains all the information about a feature
+/// A struct that contains all the information about a feature.
+/// The name, description and counter string are markup.
 pub struct FeatureInfo {
     pub key: KeyCode,
-    pub name: StyledContent<&'static str>,
-    pub description: StyledContent<&'static str>,
-    pub visible_count: i32,
-    pub unlock_count: i32,
-    pub counter_string: Option<StyledContent<&'static str>>,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub visible_count: u64,
+    pub unlock_count: u64,
+    pub counter_string: Option<&'static str>,
 }
 
 /// Feature trait:
@@ -20,13 +21,27 @@ pub trait Feature {
     fn get_info(&self) -> FeatureInfo;
 
     /// Get the top bar of this feature
-    fn get_top_bar(&self, state: &State) -> Vec<StyledContent<String>>;
+    fn get_top_bar(&self, state: &State) -> Line;
 
     /// Check if this feature is unlocked
     fn is_unlocked(&self, _state: &State) -> bool {
         true
     }
 
+    /// Check if this feature can be selected: it is unlocked and the count is high enough
+    fn is_available(&self, state: &State) -> bool {
+        self.is_unlocked(state) && state.count >= self.get_info().unlock_count
+    }
+
+    /// Update the feature in the background, every step, whether it is selected or not
+    fn tick(
+        &mut self,
+        _delta: f32,
+        _state: &mut State,
+        _message: &mut crate::message::MessageManager,
+    ) {
+    }
+
     /// Update the feature
     fn update(
         &mut self,
@@ -36,12 +51,19 @@ pub trait Feature {
     );
 
     /// Render the feature
-    fn render(&self, state: &State, features: &Vec<Box<dyn Feature>>)
-        -> Vec<StyledContent<String>>;
+    fn render(&self, state: &State, features: &[Box<dyn Feature>]) -> Vec<Line>;
+
+    /// Render a short summary of the feature for a dashboard panel, if it has one
+    fn render_compact(&self, _state: &State) -> Option<Vec<Line>> {
+        None
+    }
 }
 
+pub mod character;
 pub mod counter;
+pub mod dashboard;
 pub mod exit;
 pub mod fight;
 pub mod inventory;
+pub mod prestige;
 pub mod shop;
@@ -0,0 +1,214 @@
+use crossterm::event::KeyCode;
+use enum_iterator::{all, Sequence};
+
+use crate::{
+    feature::{fight::Stats, Feature},
+    locale::{t, tf},
+    message::{self, Message, Prompt},
+    state::State,
+    util::{layout::content_size, list::List, markup::parse, text::Line},
+};
+
+/// The stat points gained with every level
+const POINTS_PER_LEVEL: u32 = 3;
+
+/// Character feature
+/// A character sheet that shows the stats of the player.
+/// Every level gives stat points, which are spent on attributes and can be taken back with a respec.
+#[derive(Default)]
+pub struct CharacterFeature {
+    list: List,
+}
+
+/// The attributes stat points can be spent on
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum Attribute {
+    Attack,
+    Defense,
+    MaxHealth,
+    Regen,
+    Speed,
+}
+
+impl Attribute {
+    /// The translated name of this attribute
+    pub fn name(&self) -> &'static str {
+        match self {
+            Attribute::Attack => t("attribute.attack.name"),
+            Attribute::Defense => t("attribute.defense.name"),
+            Attribute::MaxHealth => t("attribute.max_health.name"),
+            Attribute::Regen => t("attribute.regen.name"),
+            Attribute::Speed => t("attribute.speed.name"),
+        }
+    }
+
+    /// The translated description of this attribute
+    pub fn description(&self) -> &'static str {
+        match self {
+            Attribute::Attack => t("attribute.attack.description"),
+            Attribute::Defense => t("attribute.defense.description"),
+            Attribute::MaxHealth => t("attribute.max_health.description"),
+            Attribute::Regen => t("attribute.regen.description"),
+            Attribute::Speed => t("attribute.speed.description"),
+        }
+    }
+
+    /// The stats a single point in this attribute adds
+    pub fn per_point(&self) -> Stats {
+        let mut stats = Stats::default();
+        match self {
+            Attribute::Attack => stats.attack = 0.5,
+            Attribute::Defense => stats.defense = 0.2,
+            Attribute::MaxHealth => stats.max_health = 2.0,
+            Attribute::Regen => stats.regen = 0.1,
+            Attribute::Speed => stats.attack_time = -0.05,
+        }
+        stats
+    }
+}
+
+/// The stat points spent on every attribute
+#[derive(Default)]
+pub struct StatPoints {
+    spent: Vec<(Attribute, u32)>,
+}
+
+impl StatPoints {
+    /// Get the points spent on an attribute
+    pub fn spent(&self, attribute: Attribute) -> u32 {
+        self.spent
+            .iter()
+            .find(|(a, _)| *a == attribute)
+            .map_or(0, |(_, points)| *points)
+    }
+
+    /// The stats all spent points add together
+    pub fn bonus(&self) -> Stats {
+        self.spent
+            .iter()
+            .map(|(attribute, points)| attribute.per_point() * *points as f64)
+            .fold(Stats::default(), |total, stats| total + stats)
+    }
+
+    fn spend(&mut self, attribute: Attribute) {
+        if let Some((_, points)) = self.spent.iter_mut().find(|(a, _)| *a == attribute) {
+            *points += 1;
+        } else {
+            self.spent.push((attribute, 1));
+        }
+    }
+}
+
+/// The stat points that can still be spent: the points of every level above the first, minus the spent ones
+pub fn available_points(state: &State) -> u32 {
+    let spent: u32 = state.fight.points.spent.iter().map(|(_, p)| p).sum();
+    (state.fight.level.saturating_sub(1) * POINTS_PER_LEVEL).saturating_sub(spent)
+}
+
+impl Feature for CharacterFeature {
+    fn get_info(&self) -> super::FeatureInfo {
+        super::FeatureInfo {
+            key: KeyCode::Char('h'),
+            name: t("feature.character.name"),
+            description: t("feature.character.description"),
+            visible_count: 10,
+            unlock_count: 10,
+            counter_string: Some(t("feature.character.condition")),
+        }
+    }
+
+    fn get_top_bar(&self, state: &State) -> Line {
+        parse(&tf(
+            "character.top_bar",
+            &[("points", &available_points(state))],
+        ))
+    }
+
+    /// The character sheet opens once the first level up gave stat points
+    fn is_unlocked(&self, state: &State) -> bool {
+        state.fight.level > 1
+    }
+
+    fn update(&mut self, _: f32, state: &mut State, message: &mut message::MessageManager) {
+        if state.key == KeyCode::Char('r') {
+            message.add_prompt(Prompt {
+                text: parse(t("character.respec_confirm")),
+                choices: vec!['y', 'n'],
+                category: message::MessageCategory::Fight,
+                callback: Box::new(|choice, state, _| {
+                    if choice == 'y' {
+                        respec(state);
+                    }
+                }),
+            });
+        }
+
+        let attributes: Vec<Attribute> = all::<Attribute>().collect();
+        let height = content_size().1 as usize;
+        if let Some(i) = self.list.process_input(state.key, attributes.len(), height) {
+            spend(attributes[i], state, message);
+        }
+    }
+
+    fn render(&self, state: &State, _: &[Box<dyn Feature>]) -> Vec<Line> {
+        let data = &state.fight;
+        let mut lines = vec![
+            parse(&tf(
+                "character.level",
+                &[("level", &data.level), ("points", &available_points(state))],
+            )),
+            parse(&tf(
+                "character.stats",
+                &[
+                    ("attack", &format!("{:.2}", data.player.attack)),
+                    ("defense", &format!("{:.2}", data.player.defense)),
+                    ("max_health", &format!("{:.2}", data.player.max_health)),
+                    ("regen", &format!("{:.2}", data.regen)),
+                    ("attack_time", &format!("{:.2}", data.attack_max)),
+                ],
+            )),
+            Line::default(),
+        ];
+
+        let attributes = all::<Attribute>()
+            .map(|a| {
+                parse(&tf(
+                    "character.attribute",
+                    &[
+                        ("points", &data.points.spent(a)),
+                        ("name", &a.name()),
+                        ("bonus", &super::fight::equipment::describe(&a.per_point())),
+                        ("description", &a.description()),
+                    ],
+                ))
+            })
+            .collect();
+
+        let (width, height) = content_size();
+        let height = (height as usize).saturating_sub(lines.len());
+        lines.append(&mut self.list.render(attributes, width, height));
+        lines
+    }
+}
+
+/// Spend a stat point on an attribute, if there is one left
+fn spend(attribute: Attribute, state: &mut State, message: &mut message::MessageManager) {
+    if available_points(state) > 0 {
+        state.fight.points.spend(attribute);
+        state.fight.update_stats();
+    } else {
+        message.add_message(Message {
+            text: parse(t("character.no_points")),
+            location: message::TextLocation::BottomRight,
+            duration: 2.0,
+            category: message::MessageCategory::Fight,
+            priority: message::Priority::Info,
+        });
+    }
+}
+
+/// Take back all spent stat points
+fn respec(state: &mut State) {
+    state.fight.points = StatPoints::default();
+    state.fight.update_stats();
+}
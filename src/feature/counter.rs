@@ -1,79 +1,396 @@
-use crate::{feature::Feature, message, state::State};
-use crossterm::{
-    event::KeyCode,
-    style::{StyledContent, Stylize},
+use crate::{
+    feature::{prestige::Bonus, Feature},
+    locale::{t, tf},
+    message,
+    state::State,
+    util::{layout::content_size, list::List, markup::parse, number::BigNum, text::Line},
 };
+use crossterm::event::KeyCode;
 
 /// Counter feature
 /// A simple feature that increments a counter when the 'c' key is pressed.
-pub struct CounterFeature;
+/// Generators bought with count increment it automatically, even when another feature is selected.
+#[derive(Default)]
+pub struct CounterFeature {
+    list: List,
+}
+
+/// A generator that produces count every second
+pub struct Generator {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub base_cost: BigNum,
+    /// The cost is multiplied by this for every generator of this kind that is owned
+    pub cost_growth: f64,
+    pub production: BigNum,
+}
+
+impl Generator {
+    /// The cost of the next generator, when `owned` are already owned
+    pub fn cost(&self, owned: u32) -> BigNum {
+        (self.base_cost * BigNum::from(self.cost_growth).pow(owned as f64)).floor()
+    }
+}
+
+pub fn get_all_generators() -> Vec<Generator> {
+    vec![
+        Generator {
+            id: "Clicker".to_string(),
+            name: t("generator.clicker.name").to_string(),
+            description: t("generator.clicker.description").to_string(),
+            base_cost: BigNum::from(15.0),
+            cost_growth: 1.15,
+            production: BigNum::from(0.2),
+        },
+        Generator {
+            id: "Workshop".to_string(),
+            name: t("generator.workshop.name").to_string(),
+            description: t("generator.workshop.description").to_string(),
+            base_cost: BigNum::from(100.0),
+            cost_growth: 1.15,
+            production: BigNum::from(1.0),
+        },
+        Generator {
+            id: "Factory".to_string(),
+            name: t("generator.factory.name").to_string(),
+            description: t("generator.factory.description").to_string(),
+            base_cost: BigNum::from(1100.0),
+            cost_growth: 1.15,
+            production: BigNum::from(8.0),
+        },
+    ]
+}
+
+/// Get all generators that are shown: the first one, and every one after a generator that is owned
+fn get_visible_generators(state: &State) -> Vec<Generator> {
+    let mut previous_owned = true;
+    get_all_generators()
+        .into_iter()
+        .take_while(|g| {
+            let visible = previous_owned;
+            previous_owned = state.generators.count(&g.id) > 0;
+            visible
+        })
+        .collect()
+}
+
+/// A reward for reaching a milestone
+pub enum Reward {
+    /// Multiplies the count of every click
+    ClickMultiplier(f64),
+    Gold(u64),
+    InventorySlots(u32),
+}
+
+impl Reward {
+    /// Describe the reward as markup
+    fn describe(&self) -> String {
+        match self {
+            Reward::ClickMultiplier(m) => tf("milestone.reward.click", &[("multiplier", m)]),
+            Reward::Gold(gold) => tf("milestone.reward.gold", &[("gold", gold)]),
+            Reward::InventorySlots(slots) => tf("milestone.reward.slots", &[("slots", slots)]),
+        }
+    }
+}
+
+/// A count that gives a reward when it is reached
+pub struct Milestone {
+    pub count: u64,
+    pub reward: Reward,
+}
+
+pub fn get_all_milestones() -> Vec<Milestone> {
+    vec![
+        Milestone {
+            count: 50,
+            reward: Reward::Gold(10),
+        },
+        Milestone {
+            count: 250,
+            reward: Reward::ClickMultiplier(2.0),
+        },
+        Milestone {
+            count: 1000,
+            reward: Reward::InventorySlots(5),
+        },
+        Milestone {
+            count: 5000,
+            reward: Reward::Gold(500),
+        },
+        Milestone {
+            count: 25000,
+            reward: Reward::ClickMultiplier(2.0),
+        },
+        Milestone {
+            count: 100000,
+            reward: Reward::InventorySlots(5),
+        },
+        Milestone {
+            count: 1000000,
+            reward: Reward::ClickMultiplier(3.0),
+        },
+    ]
+}
+
+/// The milestones that have been claimed, in the order of the milestone list
+#[derive(Default)]
+pub struct Milestones {
+    claimed: usize,
+}
+
+impl Milestones {
+    /// The multiplier of a click, from all claimed milestones
+    pub fn click_multiplier(&self) -> f64 {
+        get_all_milestones()
+            .iter()
+            .take(self.claimed)
+            .map(|m| match m.reward {
+                Reward::ClickMultiplier(multiplier) => multiplier,
+                _ => 1.0,
+            })
+            .product()
+    }
+}
+
+pub struct GeneratorInfo {
+    pub id: String,
+    pub count: u32,
+}
+
+#[derive(Default)]
+pub struct Generators {
+    generators: Vec<GeneratorInfo>,
+}
+
+impl Generators {
+    /// Get the amount of generators of a kind that are owned
+    pub fn count(&self, id: &str) -> u32 {
+        self.generators
+            .iter()
+            .find(|g| g.id == id)
+            .map_or(0, |g| g.count)
+    }
+
+    pub fn buy(&mut self, id: &str) {
+        if let Some(g) = self.generators.iter_mut().find(|g| g.id == id) {
+            g.count += 1;
+        } else {
+            self.generators.push(GeneratorInfo {
+                id: id.to_string(),
+                count: 1,
+            });
+        }
+    }
+
+    /// The total count produced every second by all generators
+    pub fn per_second(&self) -> BigNum {
+        get_all_generators().iter().fold(BigNum::ZERO, |total, g| {
+            total + g.production * self.count(&g.id) as f64
+        })
+    }
+}
 
 impl Feature for CounterFeature {
     fn get_info(&self) -> super::FeatureInfo {
         super::FeatureInfo {
             key: KeyCode::Char('c'),
-            name: "Counter".cyan(),
-            description: "A simple counter that increments when the 'c' key is pressed. It is the way to unlock new content".dark_grey(),
+            name: t("feature.counter.name"),
+            description: t("feature.counter.description"),
             visible_count: 0,
             unlock_count: 0,
             counter_string: None,
         }
     }
 
-    fn get_top_bar(&self, _state: &State) -> Vec<StyledContent<String>> {
-        vec![" [c]Increment".to_string().stylize()]
+    fn get_top_bar(&self, _state: &State) -> Line {
+        parse(t("counter.top_bar"))
+    }
+
+    fn tick(&mut self, delta: f32, state: &mut State, message: &mut message::MessageManager) {
+        state.count += count_per_second(state) * delta as f64;
+        claim_milestones(state, message);
     }
 
     fn update(&mut self, _: f32, state: &mut State, message: &mut message::MessageManager) {
         if state.key == KeyCode::Char('c') {
-            state.count += 1;
+            state.count += count_per_click(state);
         }
 
         if state.count == 0 {
             message.add_message(message::Message {
-                    text: "Keep going until you reach 10 count, you will unlock the fight feature! You can hide messages by pressing [Enter]."
-                        .to_string().bold(),
-                    location: message::TextLocation::Center,
-                    duration: 10.0,
-                });
-            state.count += 1;
+                text: parse(t("counter.tutorial")),
+                location: message::TextLocation::Center,
+                duration: 10.0,
+                category: message::MessageCategory::Tutorial,
+                priority: message::Priority::Info,
+            });
+            state.count += 1.0;
+        }
+
+        let generators = get_visible_generators(state);
+        let height = content_size().1 as usize;
+        if let Some(i) = self.list.process_input(state.key, generators.len(), height) {
+            buy(&generators[i], state, message);
         }
     }
 
-    fn render(
-        &self,
-        state: &State,
-        features: &Vec<Box<dyn Feature>>,
-    ) -> Vec<StyledContent<String>> {
+    fn render(&self, state: &State, features: &[Box<dyn Feature>]) -> Vec<Line> {
         let mut lines = vec![
-            format!("Count: {}", state.count).stylize(),
-            "".to_string().stylize(),
+            parse(&tf("counter.count", &[("count", &state.count)])),
+            parse(&tf(
+                "counter.per_second",
+                &[("amount", &format!("{:.1}", count_per_second(state)))],
+            )),
+            parse(&tf(
+                "counter.per_click",
+                &[("amount", &format!("{:.1}", count_per_click(state)))],
+            )),
+            Line::default(),
         ];
         lines.append(&mut get_unlocks(state, features));
+        lines.push(Line::default());
+        lines.append(&mut get_milestones(state));
+        lines.push(Line::default());
+
+        let generators = get_visible_generators(state)
+            .iter()
+            .map(|g| {
+                let owned = state.generators.count(&g.id);
+                parse(&tf(
+                    "counter.generator",
+                    &[
+                        ("count", &owned),
+                        ("cost", &g.cost(owned)),
+                        ("name", &g.name),
+                        ("production", &format!("{:.1}", g.production)),
+                        ("description", &g.description),
+                    ],
+                ))
+            })
+            .collect();
+
+        let (width, height) = content_size();
+        let height = (height as usize).saturating_sub(lines.len());
+        lines.append(&mut self.list.render(generators, width, height));
         lines
     }
+
+    fn render_compact(&self, state: &State) -> Option<Vec<Line>> {
+        Some(vec![
+            parse(&tf("counter.count", &[("count", &state.count)])),
+            parse(&tf(
+                "counter.per_second",
+                &[("amount", &format!("{:.1}", count_per_second(state)))],
+            )),
+        ])
+    }
+}
+
+/// The count of a single click, with the milestone and prestige bonuses
+fn count_per_click(state: &State) -> BigNum {
+    BigNum::from(state.milestones.click_multiplier() * state.prestige.multiplier(Bonus::Count))
+}
+
+/// The count produced every second by all generators, with the prestige bonus
+fn count_per_second(state: &State) -> BigNum {
+    state.generators.per_second() * state.prestige.multiplier(Bonus::Count)
+}
+
+/// Buy a generator if the player has enough count for it
+fn buy(generator: &Generator, state: &mut State, message: &mut message::MessageManager) {
+    let cost = generator.cost(state.generators.count(&generator.id));
+    if state.count >= cost {
+        state.count -= cost;
+        state.generators.buy(&generator.id);
+    } else {
+        message.add_message(message::Message {
+            text: parse(&tf("counter.not_enough", &[("cost", &cost)])),
+            location: message::TextLocation::BottomRight,
+            duration: 2.0,
+            category: message::MessageCategory::Counter,
+            priority: message::Priority::Info,
+        });
+    }
+}
+
+/// Claim every milestone the count has reached, and give its reward
+fn claim_milestones(state: &mut State, message: &mut message::MessageManager) {
+    let milestones = get_all_milestones();
+    while let Some(milestone) = milestones.get(state.milestones.claimed) {
+        if state.count < milestone.count {
+            break;
+        }
+        state.milestones.claimed += 1;
+
+        match milestone.reward {
+            Reward::ClickMultiplier(_) => {}
+            Reward::Gold(gold) => {
+                state.inventory.add("Gold", BigNum::from(gold));
+            }
+            Reward::InventorySlots(slots) => state.inventory.add_slots(slots),
+        }
+
+        message.add_message(message::Message {
+            text: parse(&tf(
+                "counter.milestone.reached",
+                &[
+                    ("count", &BigNum::from(milestone.count)),
+                    ("reward", &milestone.reward.describe()),
+                ],
+            )),
+            location: message::TextLocation::TopRight,
+            duration: 4.0,
+            category: message::MessageCategory::Counter,
+            priority: message::Priority::Info,
+        });
+    }
+}
+
+/// Get the last claimed milestones and the next upcoming ones
+fn get_milestones(state: &State) -> Vec<Line> {
+    let claimed = state.milestones.claimed;
+    let mut lines = vec![parse(t("counter.milestones"))];
+    get_all_milestones()
+        .iter()
+        .enumerate()
+        .skip(claimed.saturating_sub(2))
+        .take(4)
+        .for_each(|(i, m)| {
+            lines.push(parse(&tf(
+                if i < claimed {
+                    "counter.milestone.claimed"
+                } else {
+                    "counter.milestone.upcoming"
+                },
+                &[
+                    ("count", &BigNum::from(m.count)),
+                    ("reward", &m.reward.describe()),
+                ],
+            )));
+        });
+    lines
 }
 
-fn get_unlocks(state: &State, features: &Vec<Box<dyn Feature>>) -> Vec<StyledContent<String>> {
+fn get_unlocks(state: &State, features: &[Box<dyn Feature>]) -> Vec<Line> {
     let mut unlocks = vec![];
 
     for feature in features {
         let info = feature.get_info();
 
-        if feature.is_unlocked(state) && state.count >= info.unlock_count {
+        if feature.is_available(state) {
             continue;
         }
 
         if state.count >= info.visible_count {
-            unlocks.push(
-                format!(
-                    "{}{} unlocks {}",
-                    info.unlock_count,
-                    info.counter_string.unwrap_or("".stylize()),
-                    info.name
-                )
-                .stylize(),
-            );
+            unlocks.push(parse(&tf(
+                "counter.unlock",
+                &[
+                    ("count", &BigNum::from(info.unlock_count)),
+                    ("condition", &info.counter_string.unwrap_or("")),
+                    ("name", &info.name),
+                ],
+            )));
         }
     }
 
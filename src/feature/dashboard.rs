@@ -0,0 +1,95 @@
+use crate::{
+    feature::Feature,
+    locale::t,
+    message,
+    state::State,
+    util::{
+        layout::{content_size, Direction, Layout, Panel, Size},
+        markup::parse,
+        text::Line,
+    },
+};
+use crossterm::event::KeyCode;
+
+/// Amount of panels next to each other
+const COLUMNS: usize = 2;
+/// Amount of messages shown in the message panel
+const MESSAGE_COUNT: usize = 5;
+
+/// Dashboard feature
+/// A feature that shows a compact panel of every unlocked feature, and the latest messages, on one screen.
+#[derive(Default)]
+pub struct DashboardFeature {
+    latest: Vec<Line>,
+}
+
+impl Feature for DashboardFeature {
+    fn get_info(&self) -> super::FeatureInfo {
+        super::FeatureInfo {
+            key: KeyCode::Char('d'),
+            name: t("feature.dashboard.name"),
+            description: t("feature.dashboard.description"),
+            visible_count: 50,
+            unlock_count: 100,
+            counter_string: None,
+        }
+    }
+
+    fn get_top_bar(&self, _state: &State) -> Line {
+        Line::default()
+    }
+
+    fn update(&mut self, _: f32, _: &mut State, message: &mut message::MessageManager) {
+        self.latest = message.log.latest(MESSAGE_COUNT);
+    }
+
+    fn render(&self, state: &State, features: &[Box<dyn Feature>]) -> Vec<Line> {
+        let panels: Vec<(Size, Layout)> = features
+            .iter()
+            .filter(|f| f.is_available(state))
+            .filter_map(|f| {
+                f.render_compact(state).map(|lines| Panel {
+                    title: parse(f.get_info().name),
+                    lines,
+                    border: true,
+                })
+            })
+            .collect::<Vec<_>>()
+            .chunks(COLUMNS)
+            .map(|row| {
+                let height = row.iter().map(|p| p.lines.len()).max().unwrap_or(0) as u16 + 2;
+                let mut columns: Vec<(Size, Layout)> = row
+                    .iter()
+                    .map(|p| (Size::Ratio(1), Layout::Panel(p.clone())))
+                    .collect();
+                // keep the columns lined up when the last row is not full
+                columns.resize_with(COLUMNS, || {
+                    (
+                        Size::Ratio(1),
+                        Layout::Panel(Panel {
+                            title: Line::default(),
+                            lines: vec![],
+                            border: false,
+                        }),
+                    )
+                });
+                (
+                    Size::Fixed(height),
+                    Layout::Split(Direction::Horizontal, columns),
+                )
+            })
+            .collect();
+
+        let messages = Panel {
+            title: parse(t("dashboard.messages")),
+            lines: self.latest.clone(),
+            border: true,
+        };
+
+        let mut rows = panels;
+        rows.push((Size::Ratio(1), Layout::Panel(messages)));
+
+        let (width, height) = content_size();
+        Layout::Split(Direction::Vertical, rows).render(width, height)
+    }
+}
@@ -1,8 +1,10 @@
-use crate::{feature::Feature, state::State};
-use crossterm::{
-    event::KeyCode,
-    style::{StyledContent, Stylize},
+use crate::{
+    feature::Feature,
+    locale::t,
+    state::State,
+    util::{markup::parse, text::Line},
 };
+use crossterm::event::KeyCode;
 
 /// Exit feature
 /// A simple feature that exits the application whenever it is selected.
@@ -12,23 +14,23 @@ impl Feature for ExitFeature {
     fn get_info(&self) -> super::FeatureInfo {
         super::FeatureInfo {
             key: KeyCode::Esc,
-            name: "Quit".dark_grey(),
-            description: "Exit the application.".dark_grey(),
+            name: t("feature.exit.name"),
+            description: t("feature.exit.description"),
             visible_count: 0,
             unlock_count: 0,
             counter_string: None,
         }
     }
 
-    fn get_top_bar(&self, _state: &State) -> Vec<StyledContent<String>> {
-        vec![]
+    fn get_top_bar(&self, _state: &State) -> Line {
+        Line::default()
     }
 
     fn update(&mut self, _: f32, state: &mut State, _: &mut crate::message::MessageManager) {
         state.quit = true;
     }
 
-    fn render(&self, _: &State, _: &Vec<Box<dyn Feature>>) -> Vec<StyledContent<String>> {
-        vec!["See you later!".to_string().stylize()]
+    fn render(&self, _: &State, _: &[Box<dyn Feature>]) -> Vec<Line> {
+        vec![parse(t("exit.goodbye"))]
     }
 }
@@ -1,22 +1,67 @@
-use crate::{feature::Feature, state::State, util::flag::Flag, util::flag::Flags};
-use crossterm::{
-    event::KeyCode,
-    style::{StyledContent, Stylize},
+use crate::{
+    feature::{
+        character::{self, StatPoints},
+        inventory::get_item,
+        prestige::Bonus,
+        Feature,
+    },
+    locale::{t, tf, tn},
+    message::{self, Message, Prompt},
+    state::State,
+    theme::Role,
+    util::flag::Flag,
+    util::flag::Flags,
+    util::{
+        commands::Gauge,
+        conv::wrap,
+        layout::content_size,
+        markup::{escape, parse},
+        number::BigNum,
+        rng::{Rng, Stream},
+        text::Line,
+    },
 };
-use enum_iterator::Sequence;
+use auto::AutoBattle;
+use combat::Strike;
+use crossterm::event::KeyCode;
+use effect::{Effect, EffectKind};
+use enemy::Enemy;
+use enum_iterator::{all, cardinality, Sequence};
+use equipment::{Equipment, Slot};
+use loot::LootDrop;
+use skill::{Cost, Skill};
+use std::{
+    collections::VecDeque,
+    ops::{Add, Mul},
+};
+
+/// Width of the bars in the fight screen
+const GAUGE_WIDTH: u16 = 20;
+/// Width of the labels in front of the bars, so they line up
+const GAUGE_LABEL_WIDTH: usize = 12;
+/// Width of the bars in the dashboard panel
+const COMPACT_GAUGE_WIDTH: u16 = 10;
+/// The shortest time between attacks of the player, however fast their gear is
+const MIN_ATTACK_TIME: f32 = 0.2;
+/// Lines kept in the combat log, older lines are dropped
+const LOG_CAP: usize = 50;
+/// Most lines of the combat log shown in the fight screen, when there is room for them
+const LOG_SHOWN: usize = 5;
 
 /// Fight feature
-/// A feature that allows the player to fight enemies.
+/// A feature that allows the player to fight enemies from the enemy roster.
 /// The player can move up and down floors, attack enemies and collect gold.
 /// The player can also level up and gain more health.
 /// The player can die and respawn.
 pub struct FightFeature {
     flags: Flags<FightFlag, FightData>,
+    show_log: bool,
 }
 impl Default for FightFeature {
     fn default() -> FightFeature {
         FightFeature {
             flags: Flags::new(),
+            show_log: true,
         }
     }
 }
@@ -24,6 +69,7 @@ impl Default for FightFeature {
 #[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
 enum FightFlag {
     Attack,
+    UseSkill(Skill),
     EnemyAttack,
     Respawn,
     EnemyDead,
@@ -35,19 +81,76 @@ impl Flag<FightData> for FightFlag {
         let data = &mut state.fight;
         match self {
             FightFlag::Attack => {
-                if let Some(enemy) = &mut data.enemy {
-                    enemy.health -= (data.player.attack - enemy.defense).max(0.0);
-                    data.attack_timer = data.attack_max;
-
-                    if enemy.health <= 0.0 {
-                        flags.mark(FightFlag::EnemyDead)
+                hit_enemy(flags, data, 1.0, state.rng.stream(Stream::Combat));
+                data.attack_timer = data.attack_max;
+            }
+            FightFlag::UseSkill(skill) => {
+                // the enemy can die between marking and handling, so nothing is paid for a wasted skill
+                let has_target = data.enemy.as_ref().is_some_and(|e| e.stats.health > 0.0);
+                if skill.needs_enemy() && !has_target {
+                    return;
+                }
+                match skill.cost() {
+                    Cost::Mana(mana) => data.mana -= mana,
+                    Cost::Gold(gold) => state.inventory.remove("Gold", BigNum::from(gold)),
+                }
+                data.skill_timers[*skill as usize] = skill.cooldown();
+                data.log(tf(
+                    "fight.log.skill",
+                    &[("name", &t(&format!("{}.name", skill.name_key())))],
+                ));
+
+                let rng = state.rng.stream(Stream::Combat);
+                match skill {
+                    Skill::HeavyStrike => hit_enemy(flags, data, skill::HEAVY_STRIKE_POWER, rng),
+                    Skill::Heal => {
+                        let player = &mut data.player;
+                        player.health = (player.health + player.max_health * skill::HEAL_AMOUNT)
+                            .min(player.max_health);
+                        player.apply_effect(Effect {
+                            kind: EffectKind::Regen,
+                            strength: player.max_health * skill::HEAL_REGEN,
+                            remaining: skill::HEAL_REGEN_DURATION,
+                        });
+                    }
+                    Skill::ShieldBash => {
+                        hit_enemy(flags, data, 1.0, rng);
+                        if let Some(enemy) = &mut data.enemy {
+                            enemy.stats.apply_effect(Effect {
+                                kind: EffectKind::Stun,
+                                strength: 0.0,
+                                remaining: skill::SHIELD_BASH_STUN,
+                            });
+                        }
+                        data.player.apply_effect(Effect {
+                            kind: EffectKind::Shield,
+                            strength: data.player.attack * skill::SHIELD_BASH_SHIELD,
+                            remaining: skill::SHIELD_BASH_SHIELD_DURATION,
+                        });
+                    }
+                    Skill::Whirlwind => {
+                        for _ in 0..skill::WHIRLWIND_HITS {
+                            hit_enemy(flags, data, skill::WHIRLWIND_POWER, rng);
+                        }
                     }
                 }
             }
             FightFlag::EnemyAttack => {
-                if let Some(enemy) = &mut data.enemy {
-                    data.player.health -= (enemy.attack - data.player.defense).max(0.0);
+                if let Some(enemy) = &data.enemy {
+                    let hit = combat::strike(
+                        &enemy.stats,
+                        &data.player,
+                        1.0,
+                        state.rng.stream(Stream::Combat),
+                    );
+                    data.player.take_damage(hit.damage());
+                    if let (Some(on_hit), Strike::Hit(_) | Strike::Crit(_)) = (enemy.on_hit, hit) {
+                        on_hit.roll(&mut data.player, state.rng.stream(Stream::Combat));
+                    }
+                    data.enemy_strike = Some(hit);
                     data.enemy_timer = data.enemy_max;
+                    let text = strike_text("fight.enemy_hit", hit, &escape(&enemy.name));
+                    data.log(text);
 
                     if data.player.health <= 0.0 {
                         flags.mark(FightFlag::PlayerDead);
@@ -57,70 +160,209 @@ impl Flag<FightData> for FightFlag {
             FightFlag::Respawn => {
                 if data.floor > 0 {
                     // only spawn enemy if not on floor 0
-                    data.enemy = Some(get_enemy(data.floor));
+                    data.enemy = enemy::spawn(data.floor, state.rng.stream(Stream::Combat));
+                    if let Some(enemy) = &data.enemy {
+                        let text = tf("fight.log.appears", &[("name", &escape(&enemy.name))]);
+                        data.log(text);
+                    }
+                    data.player_strike = None;
+                    data.enemy_strike = None;
                     data.enemy_timer = data.enemy_max;
                 }
             }
             FightFlag::EnemyDead => {
-                data.enemy = None;
+                let name = data
+                    .enemy
+                    .as_ref()
+                    .map(|e| escape(&e.name))
+                    .unwrap_or_default();
+                if let Some(enemy) = data.enemy.take() {
+                    for item in loot::roll(&enemy.id, data.floor, state.rng.stream(Stream::Loot)) {
+                        let added = state.inventory.add(&item, BigNum::from(1.0));
+                        data.drops.push(LootDrop {
+                            enemy: enemy.name.clone(),
+                            item,
+                            added,
+                        });
+                    }
+                }
                 data.enemy_timer = data.enemy_max;
 
                 data.enemy_count += 1;
-                if data.enemy_count >= data.enemy_required {
+                let goal_met = data.enemy_count >= data.enemy_required;
+                if goal_met {
                     data.enemy_count = 0;
                     data.enemy_required += 1;
                     data.max_floor += 1;
                 }
 
-                state.inventory.add("Gold", data.floor as u64);
-                state.inventory.add("XP", data.floor as u64);
+                let reward = BigNum::from(data.floor);
+                let gold = reward * state.prestige.multiplier(Bonus::Gold);
+                let xp = reward * state.prestige.multiplier(Bonus::Xp);
+                state.inventory.add("Gold", gold.floor());
+                state.inventory.add("XP", xp.floor());
+                data.log(tf(
+                    "fight.log.enemy_dead",
+                    &[
+                        ("name", &name),
+                        ("gold", &gold.floor()),
+                        ("xp", &xp.floor()),
+                    ],
+                ));
 
                 if state.inventory.get_amount("XP") >= data.xp_to_next_level {
                     state.inventory.remove("XP", data.xp_to_next_level);
 
-                    let xp_increase = 10.0 * 1.15f32.powi(data.level as i32);
+                    let xp_increase = BigNum::from(1.15).pow(data.level as f64) * 10.0;
 
-                    data.xp_to_next_level += xp_increase as u64;
+                    data.xp_to_next_level += xp_increase.floor();
                     data.level += 1;
+                    data.log(tf("fight.log.level_up", &[("level", &data.level)]));
+                }
+
+                if goal_met && data.auto.enabled && data.auto.advance {
+                    data.go_to_floor((data.floor + 1).min(data.max_floor));
                 }
             }
             FightFlag::PlayerDead => {
                 data.player.health = data.player.max_health;
-                data.enemy = None;
-                data.floor = 0;
+                data.player.effects.clear();
+                data.go_to_floor(0);
 
-                state
-                    .inventory
-                    .remove("Gold", state.inventory.get_amount("Gold") >> 1);
+                let penalty = death_penalty(state);
+                state.inventory.remove("Gold", penalty);
+                state.fight.log(tn("fight.log.player_dead", penalty, &[]));
             }
         };
     }
 }
 
+impl FightFeature {
+    /// Handle the marked flags, with messages for deaths, drops and level ups
+    fn handle_flags(&mut self, state: &mut State, message: &mut message::MessageManager) {
+        let level = state.fight.level;
+        if self.flags.is_marked(&FightFlag::PlayerDead) {
+            message.add_message(Message {
+                text: parse(&tn("fight.died", death_penalty(state), &[])),
+                location: message::TextLocation::Center,
+                duration: 3.0,
+                category: message::MessageCategory::Fight,
+                priority: message::Priority::Critical,
+            });
+        }
+
+        self.flags.handle(state);
+
+        let drops: Vec<LootDrop> = state.fight.drops.drain(..).collect();
+        for drop in drops {
+            let text = match get_item(&drop.item) {
+                Some(item) if drop.added => tf(
+                    "fight.drop",
+                    &[
+                        ("enemy", &escape(&drop.enemy)),
+                        ("item", &item.styled_name()),
+                    ],
+                ),
+                Some(item) => tf("fight.inventory_full", &[("item", &item.styled_name())]),
+                None => continue,
+            };
+            state.fight.log(text.clone());
+            message.add_message(Message {
+                text: parse(&text),
+                location: message::TextLocation::TopRight,
+                duration: 3.0,
+                category: message::MessageCategory::Fight,
+                priority: message::Priority::Info,
+            });
+        }
+
+        if state.fight.level > level {
+            message.add_message(Message {
+                text: parse(&tf(
+                    "fight.level_up",
+                    &[
+                        ("level", &state.fight.level),
+                        ("points", &character::available_points(state)),
+                    ],
+                )),
+                location: message::TextLocation::TopRight,
+                duration: 3.0,
+                category: message::MessageCategory::Fight,
+                priority: message::Priority::Info,
+            });
+        }
+    }
+}
+
 impl Feature for FightFeature {
     fn get_info(&self) -> super::FeatureInfo {
         super::FeatureInfo {
             key: KeyCode::Char('f'),
-            name: "Fight".red(),
-            description: "Fight enemies, collect gold and XP.".dark_grey(),
+            name: t("feature.fight.name"),
+            description: t("feature.fight.description"),
             visible_count: 0,
             unlock_count: 10,
             counter_string: None,
         }
     }
 
-    fn get_top_bar(&self, _state: &State) -> Vec<StyledContent<String>> {
-        vec![
-            " [<]Go down a floor ".to_string().stylize(),
-            "[>]Go up a floor ".to_string().stylize(),
-            "[a]Attack".to_string().stylize(),
-        ]
+    fn get_top_bar(&self, state: &State) -> Line {
+        let skills: Vec<String> = all::<Skill>()
+            .filter(|s| s.is_unlocked(state))
+            .map(|skill| {
+                let timer = state.fight.skill_timers[skill as usize];
+                let key = if timer > 0.0 {
+                    "fight.skill.cooldown"
+                } else if skill.can_pay(state) {
+                    "fight.skill.ready"
+                } else {
+                    "fight.skill.cost"
+                };
+                tf(
+                    key,
+                    &[
+                        ("key", &skill.key()),
+                        ("name", &t(&format!("{}.name", skill.name_key()))),
+                        ("time", &format!("{:.1}", timer)),
+                    ],
+                )
+            })
+            .collect();
+        let auto = if AutoBattle::is_unlocked(state) {
+            t("fight.auto.top_bar")
+        } else {
+            ""
+        };
+        parse(&format!(
+            "{}{}{}",
+            t("fight.top_bar"),
+            auto,
+            skills.concat()
+        ))
     }
 
-    fn update(&mut self, delta: f32, state: &mut State, _: &mut crate::message::MessageManager) {
-        process_input(self, state.key, &mut state.fight);
-        self.flags.handle(state);
-        update_timers(self, delta, &mut state.fight);
+    fn tick(&mut self, delta: f32, state: &mut State, message: &mut message::MessageManager) {
+        // auto battle keeps fighting while the player is on another screen
+        if state.fight.auto.enabled {
+            let data = &mut state.fight;
+            if data.floor > 0
+                && data
+                    .auto
+                    .should_retreat(data.player.health, data.player.max_health)
+            {
+                data.go_to_floor(0);
+                message.add_message(Message {
+                    text: parse(t("fight.auto.retreated")),
+                    location: message::TextLocation::TopRight,
+                    duration: 3.0,
+                    category: message::MessageCategory::Fight,
+                    priority: message::Priority::Info,
+                });
+            }
+
+            update_timers(self, delta, &mut state.fight);
+            self.handle_flags(state, message);
+        }
 
         // simple healing at floor 0.
         if state.fight.floor == 0 && state.fight.player.health <= state.fight.player.max_health {
@@ -131,51 +373,377 @@ impl Feature for FightFeature {
         }
     }
 
-    fn render(&self, state: &State, _: &Vec<Box<dyn Feature>>) -> Vec<StyledContent<String>> {
+    fn update(&mut self, delta: f32, state: &mut State, message: &mut message::MessageManager) {
+        // without auto battle the fight only goes on on this screen, with it `tick` already ran the timers
+        let paused_elsewhere = !state.fight.auto.enabled;
+        process_input(self, state.key, &mut state.fight);
+
+        if state.key == KeyCode::Char('l') {
+            self.show_log = !self.show_log;
+        }
+
+        if AutoBattle::is_unlocked(state) {
+            let auto = &mut state.fight.auto;
+            match state.key {
+                KeyCode::Char('t') => auto.enabled = !auto.enabled,
+                KeyCode::Char('r') => auto.next_retreat(),
+                KeyCode::Char('g') => auto.advance = !auto.advance,
+                _ => {}
+            }
+        }
+
+        if let Some(skill) = Skill::from_key(state.key).filter(|s| s.is_unlocked(state)) {
+            let data = &state.fight;
+            let ready = data.skill_timers[skill as usize] <= 0.0
+                && !data.player.is_stunned()
+                && (data.enemy.is_some() || !skill.needs_enemy());
+            if ready && skill.can_pay(state) {
+                self.flags.mark(FightFlag::UseSkill(skill));
+            } else if ready {
+                let key = match skill.cost() {
+                    Cost::Mana(_) => "fight.skill.not_enough_mana",
+                    Cost::Gold(_) => "fight.skill.not_enough_gold",
+                };
+                message.add_message(Message {
+                    text: parse(t(key)),
+                    location: message::TextLocation::BottomRight,
+                    duration: 3.0,
+                    category: message::MessageCategory::Fight,
+                    priority: message::Priority::Info,
+                });
+            }
+        }
+
+        if state.key == KeyCode::Char('h') && state.fight.floor > 0 {
+            message.add_prompt(Prompt {
+                text: parse(t("fight.go_home")),
+                choices: vec!['y', 'n'],
+                category: message::MessageCategory::Fight,
+                callback: Box::new(|choice, state, _| {
+                    if choice == 'y' {
+                        state.fight.go_to_floor(0);
+                    }
+                }),
+            });
+        }
+
+        if paused_elsewhere {
+            update_timers(self, delta, &mut state.fight);
+        }
+        self.handle_flags(state, message);
+    }
+
+    fn render(&self, state: &State, _: &[Box<dyn Feature>]) -> Vec<Line> {
         let data = &state.fight;
-        vec![
-            format!(
-                "Floor: {} | Gold: {} | Level: {} | XP: {}/{}",
-                data.floor,
-                state.inventory.get_amount("Gold"),
-                data.level,
-                state.inventory.get_amount("XP"),
-                data.xp_to_next_level
-            )
-            .stylize(),
-            if data.respawn_timer != data.respawn_max {
-                format!("Respawn in {:.2}", data.respawn_timer).stylize()
-            } else {
-                if let Some(enemy) = &data.enemy {
-                    format!(
-                        "Enemy goal: {}/{} |{} Enemy HP: {:.2} | Enemy attack: {:.2}",
-                        data.enemy_count,
-                        data.enemy_required,
-                        if enemy.defense > 0.0 {
-                            format!(" Enemy defense: {:.2} |", enemy.defense).stylize()
-                        } else {
-                            "".to_string().stylize()
-                        },
-                        enemy.health,
-                        data.enemy_timer,
-                    )
-                    .stylize()
+        let gauge = |label: &str, ratio: f64, thresholds: Vec<(f64, Role)>, text: String| {
+            gauge(label, GAUGE_WIDTH, ratio, thresholds, text)
+        };
+
+        let mut lines = vec![
+            parse(&tf(
+                "fight.status",
+                &[
+                    ("floor", &data.floor),
+                    ("gold", &state.inventory.get_amount("Gold")),
+                    ("level", &data.level),
+                ],
+            )),
+            gauge(
+                "fight.gauge.xp",
+                (state.inventory.get_amount("XP") / data.xp_to_next_level).to_f64(),
+                vec![(1.0, Role::Info)],
+                format!(
+                    "{}/{}",
+                    state.inventory.get_amount("XP"),
+                    data.xp_to_next_level
+                ),
+            ),
+            gauge(
+                "fight.gauge.goal",
+                data.enemy_count as f64 / data.enemy_required as f64,
+                vec![(1.0, Role::Gold)],
+                format!("{}/{}", data.enemy_count, data.enemy_required),
+            ),
+            Line::default(),
+        ];
+
+        if data.respawn_timer != data.respawn_max {
+            lines.push(gauge(
+                "fight.gauge.respawn",
+                1.0 - (data.respawn_timer / data.respawn_max) as f64,
+                Gauge::readiness(),
+                format!("{:.2}", data.respawn_timer),
+            ));
+        } else if let Some(enemy) = &data.enemy {
+            lines.push(parse(&tf("fight.enemy", &[("name", &escape(&enemy.name))])));
+            let name = escape(&enemy.name);
+            let enemy = &enemy.stats;
+            lines.push(gauge(
+                "fight.gauge.enemy_health",
+                enemy.health / enemy.max_health,
+                Gauge::health(),
+                format!("{:.2}/{:.2}", enemy.health, enemy.max_health),
+            ));
+            lines.push(gauge(
+                "fight.gauge.enemy_attack",
+                1.0 - (data.enemy_timer / data.enemy_max) as f64,
+                vec![(0.75, Role::Dim), (1.0, Role::Danger)],
+                format!("{:.2}", data.enemy_timer),
+            ));
+            lines.extend(effects_line(&enemy.effects));
+            if enemy.defense > 0.0 {
+                lines.push(parse(&tf(
+                    "fight.enemy_defense",
+                    &[("defense", &format!("{:.2}", enemy.defense))],
+                )));
+            }
+            if let Some(hit) = data.player_strike {
+                lines.push(parse(&strike_text("fight.player_hit", hit, &name)));
+            }
+        } else {
+            lines.push(parse(t("fight.no_enemy")));
+        }
+
+        lines.push(Line::default());
+        lines.push(gauge(
+            "fight.gauge.health",
+            data.player.health / data.player.max_health,
+            Gauge::health(),
+            format!("{:.2}/{:.2}", data.player.health, data.player.max_health),
+        ));
+        lines.push(gauge(
+            "fight.gauge.attack",
+            1.0 - (data.attack_timer / data.attack_max) as f64,
+            Gauge::readiness(),
+            format!("{:.2}", data.attack_timer.max(0.0)),
+        ));
+        if all::<Skill>().any(|s| s.is_unlocked(state)) {
+            lines.push(gauge(
+                "fight.gauge.mana",
+                data.mana / data.max_mana,
+                vec![(1.0, Role::Info)],
+                format!("{:.1}/{:.1}", data.mana, data.max_mana),
+            ));
+        }
+        lines.extend(effects_line(&data.player.effects));
+        lines.push(parse(&tf(
+            "fight.damage",
+            &[("damage", &format!("{:.2}", data.player.attack))],
+        )));
+        lines.push(parse(&tf(
+            "fight.stats",
+            &[
+                ("crit", &format!("{:.0}", data.player.crit_chance * 100.0)),
+                ("multiplier", &format!("{:.1}", data.player.crit_multiplier)),
+                ("dodge", &format!("{:.0}", data.player.dodge * 100.0)),
+                ("accuracy", &format!("{:.0}", data.player.accuracy * 100.0)),
+            ],
+        )));
+        let gear: Vec<String> = all::<Slot>()
+            .map(|slot| match data.gear.get(slot).and_then(get_item) {
+                Some(item) => item.styled_name(),
+                None => t("fight.gear.empty").to_string(),
+            })
+            .collect();
+        lines.push(parse(&tf(
+            "fight.gear",
+            &[
+                ("weapon", &gear[Slot::Weapon as usize]),
+                ("armor", &gear[Slot::Armor as usize]),
+                ("accessory", &gear[Slot::Accessory as usize]),
+            ],
+        )));
+        if AutoBattle::is_unlocked(state) {
+            let on_off = |on: bool| {
+                t(if on {
+                    "fight.auto.on"
                 } else {
-                    "No enemy".to_string().stylize()
-                }
-            },
-            format!(
-                "Player HP: {:.2}/{:.2} | Damage: {:.2} | Attack: {:.2}",
-                data.player.health, data.player.max_health, data.player.attack, data.attack_timer
-            )
-            .stylize(),
-            //format!("{:?}", self.flags).stylize(),
-        ]
+                    "fight.auto.off"
+                })
+            };
+            let retreat = match data.auto.retreat_below {
+                Some(threshold) => format!("{:.0}%", threshold * 100.0),
+                None => on_off(false).to_string(),
+            };
+            lines.push(parse(&tf(
+                "fight.auto.status",
+                &[
+                    ("enabled", &on_off(data.auto.enabled)),
+                    ("retreat", &retreat),
+                    ("advance", &on_off(data.auto.advance)),
+                ],
+            )));
+        }
+        if let (Some(hit), Some(enemy)) = (data.enemy_strike, &data.enemy) {
+            lines.push(parse(&strike_text(
+                "fight.enemy_hit",
+                hit,
+                &escape(&enemy.name),
+            )));
+        }
+        //lines.push(parse(&format!("{:?}", self.flags)));
+
+        // the log goes last, and only gets the rows that are left so it never pushes the fight off the screen
+        let (width, height) = content_size();
+        let used: usize = lines.iter().map(|line| wrap(line, width).len()).sum();
+        let rows = (height as usize).saturating_sub(used + 2);
+        let shown = rows.min(LOG_SHOWN).min(data.log.len());
+        if self.show_log && shown > 0 {
+            lines.push(Line::default());
+            lines.push(parse(t("fight.log.title")));
+            lines.extend(
+                data.log
+                    .iter()
+                    .skip(data.log.len() - shown)
+                    .map(|text| parse(text)),
+            );
+        }
+
+        lines
     }
+
+    fn render_compact(&self, state: &State) -> Option<Vec<Line>> {
+        let data = &state.fight;
+        let mut lines = vec![
+            parse(&tf(
+                "fight.compact",
+                &[("floor", &data.floor), ("level", &data.level)],
+            )),
+            gauge(
+                "fight.gauge.health",
+                COMPACT_GAUGE_WIDTH,
+                data.player.health / data.player.max_health,
+                Gauge::health(),
+                format!("{:.1}", data.player.health),
+            ),
+        ];
+
+        match &data.enemy {
+            Some(enemy) => {
+                lines.push(parse(&tf("fight.enemy", &[("name", &escape(&enemy.name))])));
+                lines.push(gauge(
+                    "fight.gauge.enemy_health",
+                    COMPACT_GAUGE_WIDTH,
+                    enemy.stats.health / enemy.stats.max_health,
+                    Gauge::health(),
+                    format!("{:.1}", enemy.stats.health),
+                ));
+            }
+            None => lines.push(parse(t("fight.no_enemy"))),
+        }
+        Some(lines)
+    }
+}
+
+/// Get a labeled bar of the fight screen as a line
+fn gauge(label: &str, width: u16, ratio: f64, thresholds: Vec<(f64, Role)>, text: String) -> Line {
+    Gauge {
+        label: t(label).to_string(),
+        label_width: GAUGE_LABEL_WIDTH,
+        ratio,
+        width,
+        thresholds,
+        text,
+    }
+    .line()
+}
+
+/// Attack the enemy, if there is one that is still alive.
+/// The attack of the player is multiplied by `power`, which is 1 for a normal attack.
+fn hit_enemy(
+    flags: &mut Flags<FightFlag, FightData>,
+    data: &mut FightData,
+    power: f64,
+    rng: &mut Rng,
+) {
+    let Some(enemy) = &mut data.enemy else {
+        return;
+    };
+    let name = escape(&enemy.name);
+    let enemy = &mut enemy.stats;
+    if enemy.health <= 0.0 {
+        return;
+    }
+
+    let hit = combat::strike(&data.player, enemy, power, rng);
+    enemy.take_damage(hit.damage());
+    data.player_strike = Some(hit);
+
+    if enemy.health <= 0.0 {
+        flags.mark(FightFlag::EnemyDead)
+    }
+    data.log(strike_text("fight.player_hit", hit, &name));
+}
+
+/// Get the line with the effects on a living entity and their remaining time, if there are any
+fn effects_line(effects: &[Effect]) -> Option<Line> {
+    if effects.is_empty() {
+        return None;
+    }
+
+    let effects: Vec<String> = effects
+        .iter()
+        .map(|effect| {
+            let tag = if effect.kind.is_harmful() {
+                "danger"
+            } else {
+                "success"
+            };
+            let name = format!("{{{}}}{}{{/}}", tag, t(effect.kind.name_key()));
+            let time = format!("{:.1}", effect.remaining);
+            match effect.kind {
+                EffectKind::Stun => tf("fight.effect.timed", &[("name", &name), ("time", &time)]),
+                _ => tf(
+                    "fight.effect.strength",
+                    &[
+                        ("name", &name),
+                        ("strength", &format!("{:.1}", effect.strength)),
+                        ("time", &time),
+                    ],
+                ),
+            }
+        })
+        .collect();
+    Some(parse(&tf(
+        "fight.effects",
+        &[("effects", &effects.join(" "))],
+    )))
+}
+
+/// Get the markup telling how an attack went, with the damage and the name of the enemy
+fn strike_text(key: &str, hit: Strike, name: &str) -> String {
+    let key = match hit {
+        Strike::Miss => format!("{}.miss", key),
+        Strike::Dodge => format!("{}.dodge", key),
+        Strike::Hit(_) => format!("{}.hit", key),
+        Strike::Crit(_) => format!("{}.crit", key),
+    };
+    tf(
+        &key,
+        &[("name", &name), ("damage", &format!("{:.2}", hit.damage()))],
+    )
+}
+
+/// The gold lost when the player dies: half of it, rounded down
+fn death_penalty(state: &State) -> BigNum {
+    (state.inventory.get_amount("Gold") / 2.0).floor()
 }
 
 /// Update the fight feature timers, and set flags if the timers are up.
 fn update_timers(flags: &mut FightFeature, delta: f32, data: &mut FightData) {
+    data.player.tick_effects(delta);
+    if data.player.health <= 0.0 {
+        flags.flags.mark(FightFlag::PlayerDead);
+        return;
+    }
+    if let Some(enemy) = &mut data.enemy {
+        enemy.stats.tick_effects(delta);
+        if enemy.stats.health <= 0.0 {
+            flags.flags.mark(FightFlag::EnemyDead);
+            return;
+        }
+    }
+
     if data.respawn_timer > 0.0 && data.enemy.is_none() && data.floor > 0 {
         data.respawn_timer -= delta;
         if data.respawn_timer <= 0.0 {
@@ -184,11 +752,27 @@ fn update_timers(flags: &mut FightFeature, delta: f32, data: &mut FightData) {
         }
     }
 
-    if data.attack_timer > 0.0 && data.enemy.is_some() {
+    data.mana = (data.mana + data.mana_regen * delta as f64).min(data.max_mana);
+    for timer in data.skill_timers.iter_mut() {
+        *timer = (*timer - delta).max(0.0);
+    }
+
+    // a stun pauses the attack timer of whoever is stunned
+    if data.attack_timer > 0.0 && data.enemy.is_some() && !data.player.is_stunned() {
         data.attack_timer -= delta;
     }
 
-    if data.enemy_timer > 0.0 && data.enemy.is_some() {
+    if data.auto.enabled
+        && data.attack_timer <= 0.0
+        && data.enemy.is_some()
+        && !data.player.is_stunned()
+    {
+        data.attack_timer = data.attack_max;
+        flags.flags.mark(FightFlag::Attack);
+    }
+
+    let enemy_stunned = data.enemy.as_ref().is_some_and(|e| e.stats.is_stunned());
+    if data.enemy_timer > 0.0 && data.enemy.is_some() && !enemy_stunned {
         data.enemy_timer -= delta;
         if data.enemy_timer <= 0.0 {
             flags.flags.mark(FightFlag::EnemyAttack)
@@ -201,57 +785,75 @@ fn process_input(flags: &mut FightFeature, key: KeyCode, data: &mut FightData) {
     match key {
         KeyCode::Left => {
             // go down a floor
-            data.floor = if data.floor > 0 { data.floor - 1 } else { 0 };
-            data.respawn_timer = data.respawn_max;
-            data.attack_timer = data.attack_max;
-            data.enemy_timer = data.enemy_max;
-            data.enemy = None;
+            data.go_to_floor(data.floor.saturating_sub(1));
         }
         KeyCode::Right => {
             // go up a floor
-            data.floor = if data.floor < data.max_floor {
-                data.floor + 1
-            } else {
-                data.max_floor
-            };
-            data.respawn_timer = data.respawn_max;
-            data.attack_timer = data.attack_max;
-            data.enemy_timer = data.enemy_max;
-            data.enemy = None;
+            data.go_to_floor((data.floor + 1).min(data.max_floor));
         }
-        KeyCode::Char('a') => {
-            if data.attack_timer <= 0.0 {
-                data.attack_timer = data.attack_max;
-                flags.flags.mark(FightFlag::Attack);
-            }
+        KeyCode::Char('a') if data.attack_timer <= 0.0 && !data.player.is_stunned() => {
+            data.attack_timer = data.attack_max;
+            flags.flags.mark(FightFlag::Attack);
         }
         _ => {}
     }
 }
 
-/// Get a new enemy based on the floor
-fn get_enemy(floor: u32) -> Living {
-    Living {
-        attack: 0.9 + floor as f64 * 0.1,
-        defense: if floor >= 5 {
-            (floor - 5) as f64 * 0.1
-        } else {
-            0.0
-        },
-        health: 3.0 + floor as f64 * 2.0,
-        max_health: 3.0 + floor as f64 * 2.0,
+impl FightData {
+    /// Move to a floor, resetting the enemy and all timers
+    fn go_to_floor(&mut self, floor: u32) {
+        self.floor = floor;
+        self.respawn_timer = self.respawn_max;
+        self.attack_timer = self.attack_max;
+        self.enemy_timer = self.enemy_max;
+        self.enemy = None;
+        self.player_strike = None;
+        self.enemy_strike = None;
+    }
+
+    /// Add a line of markup to the combat log, dropping the oldest line if the log is full
+    fn log(&mut self, text: String) {
+        self.log.push_back(text);
+        if self.log.len() > LOG_CAP {
+            self.log.pop_front();
+        }
+    }
+
+    /// Set the stats of the player to the base stats with the bonus of all gear and stat points.
+    /// The stats are always computed from scratch, so taking gear off or a respec gives back the base stats exactly.
+    pub fn update_stats(&mut self) {
+        let stats = self.base + self.gear.bonus() + self.points.bonus();
+        self.player.attack = stats.attack;
+        self.player.defense = stats.defense;
+        self.player.max_health = stats.max_health;
+        self.player.health = self.player.health.min(stats.max_health);
+        self.attack_max = stats.attack_time.max(MIN_ATTACK_TIME);
+        self.regen = stats.regen;
     }
 }
 
 /// Starting state for the fight feature
 impl Default for FightData {
     fn default() -> Self {
+        let base = Stats {
+            attack: 1.0,
+            defense: 0.0,
+            max_health: 10.0,
+            attack_time: 1.5,
+            regen: 0.5,
+        };
+
         Self {
             player: Living {
-                attack: 1.0,
-                defense: 0.0,
-                health: 10.0,
-                max_health: 10.0,
+                attack: base.attack,
+                defense: base.defense,
+                health: base.max_health,
+                max_health: base.max_health,
+                crit_chance: 0.05,
+                crit_multiplier: 2.0,
+                dodge: 0.05,
+                accuracy: 0.95,
+                effects: Vec::new(),
             },
             enemy: None,
 
@@ -263,15 +865,30 @@ impl Default for FightData {
             respawn_timer: 0.0,
             respawn_max: 3.0,
 
-            attack_timer: 1.5,
-            attack_max: 1.5,
+            attack_timer: base.attack_time,
+            attack_max: base.attack_time,
 
             enemy_timer: 2.0,
             enemy_max: 2.0,
 
-            xp_to_next_level: 10,
+            xp_to_next_level: BigNum::from(10.0),
             level: 1,
-            regen: 0.5,
+            regen: base.regen,
+            base,
+            gear: Equipment::default(),
+            points: StatPoints::default(),
+
+            mana: 10.0,
+            max_mana: 10.0,
+            mana_regen: 0.2,
+            skill_timers: vec![0.0; cardinality::<Skill>()],
+
+            auto: AutoBattle::default(),
+
+            player_strike: None,
+            enemy_strike: None,
+            drops: Vec::new(),
+            log: VecDeque::new(),
         }
     }
 }
@@ -280,7 +897,7 @@ impl Default for FightData {
 /// It contains all data related to the feature
 pub struct FightData {
     pub player: Living,
-    enemy: Option<Living>,
+    enemy: Option<Enemy>,
 
     // floor data
     pub floor: u32,
@@ -297,9 +914,71 @@ pub struct FightData {
     enemy_max: f32,
 
     // player data
-    xp_to_next_level: u64,
+    xp_to_next_level: BigNum,
     pub level: u32,
     pub regen: f64,
+    /// The stats of the player without gear, which upgrades add to.
+    /// Call `update_stats` after changing them.
+    pub base: Stats,
+    pub gear: Equipment,
+    pub points: StatPoints,
+
+    // skill data
+    mana: f64,
+    pub max_mana: f64,
+    pub mana_regen: f64,
+    /// Seconds before each skill can be used again
+    skill_timers: Vec<f32>,
+
+    pub auto: AutoBattle,
+
+    /// The last attack of the player and of the enemy, to show how they went
+    player_strike: Option<Strike>,
+    enemy_strike: Option<Strike>,
+    /// Items that dropped since the last update, to tell the player about
+    drops: Vec<LootDrop>,
+    /// What happened in the fight, newest last, as markup
+    log: VecDeque<String>,
+}
+
+/// Stats of the player that gear and stat points add to
+#[derive(Default, PartialEq, Copy, Clone, Debug)]
+pub struct Stats {
+    pub attack: f64,
+    pub defense: f64,
+    pub max_health: f64,
+    /// Seconds between attacks, or for gear the change to it, where less is faster
+    pub attack_time: f32,
+    /// Health regenerated every second at floor 0
+    pub regen: f64,
+}
+
+impl Add for Stats {
+    type Output = Stats;
+
+    fn add(self, other: Stats) -> Stats {
+        Stats {
+            attack: self.attack + other.attack,
+            defense: self.defense + other.defense,
+            max_health: self.max_health + other.max_health,
+            attack_time: self.attack_time + other.attack_time,
+            regen: self.regen + other.regen,
+        }
+    }
+}
+
+impl Mul<f64> for Stats {
+    type Output = Stats;
+
+    fn mul(self, factor: f64) -> Stats {
+        Stats {
+            attack: self.attack * factor,
+            defense: self.defense * factor,
+            max_health: self.max_health * factor,
+            attack_time: self.attack_time * factor as f32,
+            regen: self.regen * factor,
+        }
+    }
 }
 
 /// Struct for the living entities in the fight feature
@@ -308,4 +987,22 @@ pub struct Living {
     pub defense: f64,
     pub health: f64,
     pub max_health: f64,
+    /// Chance of a critical hit, from 0 to 1
+    pub crit_chance: f64,
+    /// Damage of a critical hit, compared to a normal hit
+    pub crit_multiplier: f64,
+    /// Chance to dodge an attack, from 0 to 1
+    pub dodge: f64,
+    /// Chance that an attack does not miss, from 0 to 1
+    pub accuracy: f64,
+    /// Timed effects, like poison or a shield
+    pub effects: Vec<Effect>,
 }
+
+pub mod auto;
+pub mod combat;
+pub mod effect;
+pub mod enemy;
+pub mod equipment;
+pub mod loot;
+pub mod skill;
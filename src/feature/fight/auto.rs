@@ -0,0 +1,39 @@
+use crate::state::State;
+
+/// The id of the shop upgrade that unlocks auto battle
+pub const UPGRADE: &str = "AutoBattle";
+/// The level that unlocks auto battle without buying it
+pub const UNLOCK_LEVEL: u32 = 5;
+/// The health thresholds to retreat below, as part of the max health, cycled through in order
+const RETREAT_THRESHOLDS: [Option<f64>; 4] = [None, Some(0.25), Some(0.5), Some(0.75)];
+
+/// Settings of auto battle, which attacks whenever the attack is ready
+#[derive(Default)]
+pub struct AutoBattle {
+    pub enabled: bool,
+    /// Go back to floor 0 when the health of the player drops below this part of the max health
+    pub retreat_below: Option<f64>,
+    /// Go up a floor as soon as the enemy goal of the floor is met
+    pub advance: bool,
+}
+
+impl AutoBattle {
+    /// Check if auto battle is unlocked, by level or in the shop
+    pub fn is_unlocked(state: &State) -> bool {
+        state.fight.level >= UNLOCK_LEVEL || state.upgrades.contains(UPGRADE).is_some()
+    }
+
+    /// Switch to the next retreat threshold
+    pub fn next_retreat(&mut self) {
+        let i = RETREAT_THRESHOLDS
+            .iter()
+            .position(|t| *t == self.retreat_below)
+            .unwrap_or(0);
+        self.retreat_below = RETREAT_THRESHOLDS[(i + 1) % RETREAT_THRESHOLDS.len()];
+    }
+
+    /// Check if the player should retreat with this much health
+    pub fn should_retreat(&self, health: f64, max_health: f64) -> bool {
+        self.enabled && self.retreat_below.is_some_and(|t| health < max_health * t)
+    }
+}
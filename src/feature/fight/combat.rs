@@ -0,0 +1,45 @@
+use crate::util::rng::Rng;
+
+use super::Living;
+
+/// The result of a single attack
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Strike {
+    /// The attacker missed, from a failed accuracy roll
+    Miss,
+    /// The defender dodged the attack
+    Dodge,
+    /// The attack hit for an amount of damage
+    Hit(f64),
+    /// The attack hit critically, for an amount of damage
+    Crit(f64),
+}
+
+impl Strike {
+    /// The damage dealt by this attack
+    pub fn damage(self) -> f64 {
+        match self {
+            Strike::Miss | Strike::Dodge => 0.0,
+            Strike::Hit(damage) | Strike::Crit(damage) => damage,
+        }
+    }
+}
+
+/// Attack a defender, applying the steps of the damage pipeline in order:
+/// the accuracy of the attacker, the dodge of the defender, attack minus defense, and then a critical hit.
+/// The attack of the attacker is multiplied by `power`, which is 1 for a normal attack.
+pub fn strike(attacker: &Living, defender: &Living, power: f64, rng: &mut Rng) -> Strike {
+    if rng.next_f64() >= attacker.accuracy {
+        return Strike::Miss;
+    }
+    if rng.next_f64() < defender.dodge {
+        return Strike::Dodge;
+    }
+
+    let damage = (attacker.attack * power - defender.defense).max(0.0);
+    if rng.next_f64() < attacker.crit_chance {
+        Strike::Crit(damage * attacker.crit_multiplier)
+    } else {
+        Strike::Hit(damage)
+    }
+}
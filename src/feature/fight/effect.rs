@@ -0,0 +1,141 @@
+use enum_iterator::{all, Sequence};
+
+use crate::util::rng::Rng;
+
+use super::Living;
+
+/// The kinds of timed effects on a living entity
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum EffectKind {
+    /// Damage every second, stacking with every new dose
+    Poison,
+    /// Damage every second, refreshed by a new burn
+    Burn,
+    /// Damage every second, stacking with every new wound
+    Bleed,
+    /// No attacks while it lasts
+    Stun,
+    /// Healing every second, refreshed by new regeneration
+    Regen,
+    /// Absorbs damage until it is used up, stacking with every new shield
+    Shield,
+}
+
+impl EffectKind {
+    /// Get an effect kind by its name, like `poison`
+    pub fn from_name(name: &str) -> Option<EffectKind> {
+        all::<EffectKind>().find(|k| format!("{:?}", k).eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The key of the name of this effect in the language files
+    pub fn name_key(self) -> &'static str {
+        match self {
+            EffectKind::Poison => "effect.poison",
+            EffectKind::Burn => "effect.burn",
+            EffectKind::Bleed => "effect.bleed",
+            EffectKind::Stun => "effect.stun",
+            EffectKind::Regen => "effect.regen",
+            EffectKind::Shield => "effect.shield",
+        }
+    }
+
+    /// Check if this effect hurts the one it is on
+    pub fn is_harmful(self) -> bool {
+        !matches!(self, EffectKind::Regen | EffectKind::Shield)
+    }
+
+    /// Check if a new effect of this kind adds its strength to the current one.
+    /// If not, the strongest one is kept and the time is refreshed.
+    fn stacks(self) -> bool {
+        matches!(
+            self,
+            EffectKind::Poison | EffectKind::Bleed | EffectKind::Shield
+        )
+    }
+}
+
+/// A timed effect on a living entity
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Effect {
+    pub kind: EffectKind,
+    /// Damage or healing per second, or the damage a shield absorbs
+    pub strength: f64,
+    /// Seconds left before the effect wears off
+    pub remaining: f32,
+}
+
+/// An effect an attack can cause on the one it hits
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct OnHit {
+    pub chance: f64,
+    pub effect: Effect,
+}
+
+impl OnHit {
+    /// Roll the chance of the effect, and apply it to the target on success
+    pub fn roll(&self, target: &mut Living, rng: &mut Rng) {
+        if rng.next_f64() < self.chance {
+            target.apply_effect(self.effect);
+        }
+    }
+}
+
+impl Living {
+    /// Apply an effect, stacking with or refreshing an effect of the same kind
+    pub fn apply_effect(&mut self, effect: Effect) {
+        match self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            Some(current) if effect.kind.stacks() => {
+                current.strength += effect.strength;
+                current.remaining = current.remaining.max(effect.remaining);
+            }
+            Some(current) => {
+                current.strength = current.strength.max(effect.strength);
+                current.remaining = current.remaining.max(effect.remaining);
+            }
+            None => self.effects.push(effect),
+        }
+    }
+
+    /// Apply the damage and healing of all effects for the time passed, and remove the ones that wore off
+    pub fn tick_effects(&mut self, delta: f32) {
+        for effect in self.effects.clone() {
+            let amount = effect.strength * delta.min(effect.remaining) as f64;
+            match effect.kind {
+                EffectKind::Poison | EffectKind::Burn | EffectKind::Bleed => {
+                    self.take_damage(amount)
+                }
+                EffectKind::Regen => {
+                    self.health = (self.health + amount).min(self.max_health);
+                }
+                EffectKind::Stun | EffectKind::Shield => {}
+            }
+        }
+
+        for effect in self.effects.iter_mut() {
+            effect.remaining -= delta;
+        }
+        self.effects.retain(|e| e.remaining > 0.0);
+    }
+
+    /// Take damage, of which shields absorb what they can
+    pub fn take_damage(&mut self, damage: f64) {
+        let mut damage = damage;
+        for shield in self
+            .effects
+            .iter_mut()
+            .filter(|e| e.kind == EffectKind::Shield)
+        {
+            let absorbed = damage.min(shield.strength);
+            shield.strength -= absorbed;
+            damage -= absorbed;
+        }
+        self.effects
+            .retain(|e| e.kind != EffectKind::Shield || e.strength > 0.0);
+        self.health -= damage;
+    }
+
+    /// Check if this entity is stunned, and can not attack
+    pub fn is_stunned(&self) -> bool {
+        self.effects.iter().any(|e| e.kind == EffectKind::Stun)
+    }
+}
@@ -0,0 +1,253 @@
+use std::sync::OnceLock;
+
+use enum_iterator::{all, Sequence};
+
+use crate::{
+    locale::t,
+    util::{
+        config::{load_sections, parse_value},
+        rng::Rng,
+    },
+};
+
+use super::{
+    effect::{Effect, EffectKind, OnHit},
+    Living,
+};
+
+/// The file enemies are read from
+const ENEMIES_FILE: &str = "data/enemies.cfg";
+const BUILT_IN: &str = include_str!("../../../data/enemies.cfg");
+
+static ROSTER: OnceLock<Vec<EnemyType>> = OnceLock::new();
+
+/// How the stats of an enemy grow on higher floors
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum Scaling {
+    /// Stats grow by the same amount every floor
+    Linear,
+    /// Stats grow by the same factor every floor
+    Exponential,
+}
+
+impl Scaling {
+    /// Find a scaling by its name, ignoring case
+    pub fn from_name(name: &str) -> Option<Scaling> {
+        all::<Scaling>().find(|s| format!("{:?}", s).eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// The definition of a kind of enemy, as read from the roster
+#[derive(Clone, Debug)]
+pub struct EnemyType {
+    pub id: String,
+    pub name: String,
+    pub attack: f64,
+    pub defense: f64,
+    pub health: f64,
+    pub crit_chance: f64,
+    pub crit_multiplier: f64,
+    pub dodge: f64,
+    pub accuracy: f64,
+    /// The effect the attacks of this enemy can cause, with its strength at the first floor
+    pub on_hit: Option<OnHit>,
+    pub scaling: Scaling,
+    pub growth: f64,
+    pub min_floor: u32,
+    pub max_floor: Option<u32>,
+    pub weight: f64,
+}
+
+/// An enemy that is being fought
+pub struct Enemy {
+    pub id: String,
+    pub name: String,
+    pub stats: Living,
+    pub on_hit: Option<OnHit>,
+}
+
+impl EnemyType {
+    /// Read an enemy type from its section in the roster, with defaults for missing values.
+    /// Gives every unknown key and invalid value if there are any.
+    fn parse(id: &str, pairs: &[(&str, &str)]) -> Result<EnemyType, Vec<String>> {
+        let mut enemy = EnemyType {
+            id: id.to_string(),
+            name: id.to_string(),
+            attack: 1.0,
+            defense: 0.0,
+            health: 1.0,
+            crit_chance: 0.05,
+            crit_multiplier: 1.5,
+            dodge: 0.0,
+            accuracy: 0.9,
+            on_hit: None,
+            scaling: Scaling::Linear,
+            growth: 0.0,
+            min_floor: 1,
+            max_floor: None,
+            weight: 1.0,
+        };
+
+        // the effect is only set if it has a kind, the other keys can come in any order
+        let mut kind = None;
+        let mut on_hit = OnHit {
+            chance: 0.25,
+            effect: Effect {
+                kind: EffectKind::Poison,
+                strength: 1.0,
+                remaining: 3.0,
+            },
+        };
+        let mut errors = Vec::new();
+        for (key, value) in pairs {
+            let result = match *key {
+                "name" => {
+                    enemy.name = value.to_string();
+                    Ok(())
+                }
+                "attack" => parse_value(key, value).map(|v| enemy.attack = v),
+                "defense" => parse_value(key, value).map(|v| enemy.defense = v),
+                "health" => parse_value(key, value).map(|v| enemy.health = v),
+                "crit_chance" => parse_value(key, value).map(|v| enemy.crit_chance = v),
+                "crit_multiplier" => parse_value(key, value).map(|v| enemy.crit_multiplier = v),
+                "dodge" => parse_value(key, value).map(|v| enemy.dodge = v),
+                "accuracy" => parse_value(key, value).map(|v| enemy.accuracy = v),
+                "effect" => match EffectKind::from_name(value) {
+                    Some(k) if k.is_harmful() => {
+                        kind = Some(k);
+                        Ok(())
+                    }
+                    Some(_) => Err(format!("`{}` does not harm the player", value)),
+                    None => Err(format!("`{}` is not an effect", value)),
+                },
+                "effect_chance" => parse_value(key, value).map(|v| on_hit.chance = v),
+                "effect_strength" => parse_value(key, value).map(|v| on_hit.effect.strength = v),
+                "effect_duration" => parse_value(key, value).map(|v| on_hit.effect.remaining = v),
+                "scaling" => Scaling::from_name(value)
+                    .map(|s| enemy.scaling = s)
+                    .ok_or_else(|| format!("`{}` is not a scaling", value)),
+                "growth" => parse_value(key, value).map(|v| enemy.growth = v),
+                "min_floor" => parse_value(key, value).map(|v| enemy.min_floor = v),
+                "max_floor" => parse_value(key, value).map(|v| enemy.max_floor = Some(v)),
+                "weight" => parse_value(key, value).map(|v| enemy.weight = v),
+                _ => Err(format!("unknown key `{}`", key)),
+            };
+            if let Err(error) = result {
+                errors.push(error);
+            }
+        }
+
+        let chances = [
+            ("crit_chance", enemy.crit_chance),
+            ("dodge", enemy.dodge),
+            ("accuracy", enemy.accuracy),
+            ("effect_chance", on_hit.chance),
+        ];
+        for (key, chance) in chances {
+            if !(0.0..=1.0).contains(&chance) {
+                errors.push(format!("`{}` has to be between 0 and 1", key));
+            }
+        }
+        if enemy.health <= 0.0 {
+            errors.push("`health` has to be above 0".to_string());
+        }
+        if enemy.weight < 0.0 {
+            errors.push("`weight` can not be negative".to_string());
+        }
+        if enemy.max_floor.is_some_and(|max| max < enemy.min_floor) {
+            errors.push("`max_floor` is below `min_floor`".to_string());
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        enemy.on_hit = kind.map(|kind| OnHit {
+            effect: Effect {
+                kind,
+                ..on_hit.effect
+            },
+            ..on_hit
+        });
+        Ok(enemy)
+    }
+
+    /// Check if this enemy appears on a floor
+    pub fn appears_on(&self, floor: u32) -> bool {
+        floor >= self.min_floor && self.max_floor.is_none_or(|max| floor <= max)
+    }
+
+    /// The translated name of this enemy, or the name from the roster if there is no translation
+    pub fn display_name(&self) -> String {
+        let key = format!("enemy.{}.name", self.id);
+        match t(&key) {
+            name if name == key => self.name.clone(),
+            name => name.to_string(),
+        }
+    }
+
+    /// Create an enemy of this type, with its stats scaled to a floor
+    pub fn create(&self, floor: u32) -> Enemy {
+        let floors = floor.saturating_sub(self.min_floor) as f64;
+        let factor = match self.scaling {
+            Scaling::Linear => 1.0 + self.growth * floors,
+            Scaling::Exponential => (1.0 + self.growth).powf(floors),
+        };
+        let health = self.health * factor;
+
+        Enemy {
+            id: self.id.clone(),
+            name: self.display_name(),
+            on_hit: self.on_hit.map(|on_hit| OnHit {
+                effect: Effect {
+                    strength: on_hit.effect.strength * factor,
+                    ..on_hit.effect
+                },
+                ..on_hit
+            }),
+            stats: Living {
+                attack: self.attack * factor,
+                defense: self.defense * factor,
+                health,
+                max_health: health,
+                effects: Vec::new(),
+                crit_chance: self.crit_chance,
+                crit_multiplier: self.crit_multiplier,
+                dodge: self.dodge,
+                accuracy: self.accuracy,
+            },
+        }
+    }
+}
+
+/// Load the enemy roster, giving every problem in it if there are any.
+/// There has to be an enemy that can spawn on the first floor, or the player could never leave it.
+pub fn load() -> Result<(), Vec<String>> {
+    let roster = load_sections(ENEMIES_FILE, BUILT_IN, EnemyType::parse)?;
+    if !roster.iter().any(|e| e.appears_on(1) && e.weight > 0.0) {
+        return Err(vec![format!(
+            "{}: no enemy can spawn on floor 1",
+            ENEMIES_FILE
+        )]);
+    }
+    let _ = ROSTER.set(roster);
+    Ok(())
+}
+
+/// Get all enemy types, which are empty until the roster is loaded
+pub fn roster() -> &'static [EnemyType] {
+    ROSTER.get().map_or(&[], |roster| roster.as_slice())
+}
+
+/// Spawn a random enemy that appears on a floor, picked by the spawn weights.
+/// If no enemy appears on the floor, any enemy can be picked.
+pub fn spawn(floor: u32, rng: &mut Rng) -> Option<Enemy> {
+    let roster = roster();
+    let mut candidates: Vec<&EnemyType> = roster.iter().filter(|e| e.appears_on(floor)).collect();
+    if candidates.is_empty() {
+        candidates = roster.iter().collect();
+    }
+
+    let weights: Vec<f64> = candidates.iter().map(|e| e.weight).collect();
+    rng.pick_weighted(&weights)
+        .map(|i| candidates[i].create(floor))
+}
@@ -0,0 +1,143 @@
+use enum_iterator::{all, cardinality, Sequence};
+
+use crate::{
+    feature::inventory::items::items,
+    locale::{t, tf},
+    state::State,
+    util::number::BigNum,
+};
+
+use super::Stats;
+
+/// The slots gear can be equipped in
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum Slot {
+    Weapon,
+    Armor,
+    Accessory,
+}
+
+impl Slot {
+    /// Get a slot by its name, like `weapon`
+    pub fn from_name(name: &str) -> Option<Slot> {
+        all::<Slot>().find(|s| format!("{:?}", s).eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The key of the name of this slot in the language files
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Slot::Weapon => "slot.weapon",
+            Slot::Armor => "slot.armor",
+            Slot::Accessory => "slot.accessory",
+        }
+    }
+
+    /// The key that unequips this slot in the inventory
+    pub fn key(self) -> char {
+        match self {
+            Slot::Weapon => '1',
+            Slot::Armor => '2',
+            Slot::Accessory => '3',
+        }
+    }
+}
+
+/// Why an item could not be equipped or unequipped
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum EquipError {
+    /// The item does not go in a slot
+    NotGear,
+    /// The item is not in the inventory
+    Missing,
+    /// There is no room in the inventory for the item taken off
+    InventoryFull,
+    /// Nothing is equipped in the slot
+    Empty,
+}
+
+/// The ids of the items equipped in every slot
+pub struct Equipment {
+    slots: Vec<Option<String>>,
+}
+
+impl Default for Equipment {
+    fn default() -> Self {
+        Self {
+            slots: vec![None; cardinality::<Slot>()],
+        }
+    }
+}
+
+impl Equipment {
+    /// Get the id of the item in a slot
+    pub fn get(&self, slot: Slot) -> Option<&str> {
+        self.slots[slot as usize].as_deref()
+    }
+
+    /// The stats all equipped items add together
+    pub fn bonus(&self) -> Stats {
+        self.slots
+            .iter()
+            .flatten()
+            .filter_map(|id| items().iter().find(|i| i.id == *id))
+            .fold(Stats::default(), |total, item| total + item.stats)
+    }
+}
+
+/// Describe the stats gear adds, like `+1 attack, -0.1s attack time`
+pub fn describe(stats: &Stats) -> String {
+    [
+        ("stat.attack", stats.attack),
+        ("stat.defense", stats.defense),
+        ("stat.max_health", stats.max_health),
+        ("stat.attack_time", stats.attack_time as f64),
+        ("stat.regen", stats.regen),
+    ]
+    .iter()
+    .filter(|(_, value)| *value != 0.0)
+    .map(|(key, value)| tf(key, &[("value", &format!("{:+}", value))]))
+    .collect::<Vec<String>>()
+    .join(t("stat.separator"))
+}
+
+/// Equip an item from the inventory, putting the item that was in its slot back in the inventory
+pub fn equip(state: &mut State, id: &str) -> Result<Slot, EquipError> {
+    let slot = items()
+        .iter()
+        .find(|i| i.id == id)
+        .and_then(|i| i.slot)
+        .ok_or(EquipError::NotGear)?;
+    if state.inventory.get_amount(id) == 0 {
+        return Err(EquipError::Missing);
+    }
+
+    state.inventory.remove(id, BigNum::from(1.0));
+    if let Some(old) = state.fight.gear.get(slot).map(str::to_string) {
+        if !state.inventory.add(&old, BigNum::from(1.0)) {
+            // undo taking the new item out, there was no room for the old one
+            state.inventory.add(id, BigNum::from(1.0));
+            return Err(EquipError::InventoryFull);
+        }
+    }
+
+    state.fight.gear.slots[slot as usize] = Some(id.to_string());
+    state.fight.update_stats();
+    Ok(slot)
+}
+
+/// Take the item in a slot off, and put it back in the inventory
+pub fn unequip(state: &mut State, slot: Slot) -> Result<String, EquipError> {
+    let id = state
+        .fight
+        .gear
+        .get(slot)
+        .ok_or(EquipError::Empty)?
+        .to_string();
+    if !state.inventory.add(&id, BigNum::from(1.0)) {
+        return Err(EquipError::InventoryFull);
+    }
+
+    state.fight.gear.slots[slot as usize] = None;
+    state.fight.update_stats();
+    Ok(id)
+}
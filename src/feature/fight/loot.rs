@@ -0,0 +1,215 @@
+use std::sync::OnceLock;
+
+use enum_iterator::all;
+
+use crate::{
+    feature::inventory::{items::items, Rarity},
+    util::{
+        config::{load_sections, parse_value},
+        rng::Rng,
+    },
+};
+
+use super::enemy::roster;
+
+/// The file loot tables are read from
+const LOOT_FILE: &str = "data/loot.cfg";
+const BUILT_IN: &str = include_str!("../../../data/loot.cfg");
+
+static TABLES: OnceLock<Vec<LootTable>> = OnceLock::new();
+
+/// What a loot table drops for
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    /// An enemy, by its id
+    Enemy(String),
+    /// A range of floors, open ended if there is no last floor
+    Floors(u32, Option<u32>),
+}
+
+/// A table of items that can drop when an enemy dies
+#[derive(Clone, Debug)]
+pub struct LootTable {
+    pub source: Source,
+    pub chance: f64,
+    pub items: Vec<String>,
+    pub rarity_weights: Vec<(Rarity, f64)>,
+}
+
+/// An item that dropped from an enemy, and whether there was room for it in the inventory
+pub struct LootDrop {
+    pub enemy: String,
+    pub item: String,
+    pub added: bool,
+}
+
+impl LootTable {
+    /// Read a loot table from its section in the loot file.
+    /// Gives every unknown key, invalid value and unknown enemy or item if there are any,
+    /// or if the table has no enemy or floors, or no items.
+    fn parse(_: &str, pairs: &[(&str, &str)]) -> Result<LootTable, Vec<String>> {
+        let mut source = None;
+        let mut table = LootTable {
+            source: Source::Floors(0, None),
+            chance: 1.0,
+            items: Vec::new(),
+            rarity_weights: default_weights(),
+        };
+
+        let mut errors = Vec::new();
+        for (key, value) in pairs {
+            let result = match *key {
+                "enemy" if roster().iter().any(|e| e.id == *value) => {
+                    source = Some(Source::Enemy(value.to_string()));
+                    Ok(())
+                }
+                "enemy" => Err(format!("`{}` is not an enemy", value)),
+                "floors" => parse_floors(value)
+                    .map(|s| source = Some(s))
+                    .ok_or_else(|| format!("`{}` is not a range of floors", value)),
+                "chance" => parse_value(key, value).map(|v| table.chance = v),
+                "items" => {
+                    table.items = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|i| !i.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                    Ok(())
+                }
+                _ => match key.strip_prefix("rarity.") {
+                    Some(name) => Rarity::from_name(name)
+                        .ok_or_else(|| format!("`{}` is not a rarity", name))
+                        .and_then(|rarity| Ok((rarity, parse_value(key, value)?)))
+                        .map(|(rarity, weight)| table.set_weight(rarity, weight)),
+                    None => Err(format!("unknown key `{}`", key)),
+                },
+            };
+            if let Err(error) = result {
+                errors.push(error);
+            }
+        }
+
+        if table.items.is_empty() {
+            errors.push("the table has no items".to_string());
+        }
+        for id in &table.items {
+            if !items().iter().any(|i| i.id == *id) {
+                errors.push(format!("`{}` is not an item", id));
+            }
+        }
+        match source {
+            Some(source) if errors.is_empty() => Ok(LootTable { source, ..table }),
+            Some(_) => Err(errors),
+            None => {
+                errors.push("the table needs an `enemy` or `floors`".to_string());
+                Err(errors)
+            }
+        }
+    }
+
+    /// Set the weight of a rarity
+    fn set_weight(&mut self, rarity: Rarity, weight: f64) {
+        match self.rarity_weights.iter_mut().find(|(r, _)| *r == rarity) {
+            Some((_, w)) => *w = weight,
+            None => self.rarity_weights.push((rarity, weight)),
+        }
+    }
+
+    /// Check if this table drops for an enemy on a floor
+    pub fn applies(&self, enemy: &str, floor: u32) -> bool {
+        match &self.source {
+            Source::Enemy(id) => id == enemy,
+            Source::Floors(min, max) => floor >= *min && max.is_none_or(|max| floor <= max),
+        }
+    }
+
+    /// Roll this table once: first the chance, then a rarity out of the rarities of the items,
+    /// and then one of the items with that rarity
+    pub fn roll(&self, rng: &mut Rng) -> Option<String> {
+        if rng.next_f64() >= self.chance {
+            return None;
+        }
+
+        let items: Vec<(&str, Rarity)> = self
+            .items
+            .iter()
+            .filter_map(|id| {
+                items()
+                    .iter()
+                    .find(|i| i.id == *id)
+                    .map(|i| (id.as_str(), i.rarity))
+            })
+            .collect();
+
+        let rarities: Vec<Rarity> = all::<Rarity>()
+            .filter(|r| items.iter().any(|(_, rarity)| rarity == r))
+            .collect();
+        let weights: Vec<f64> = rarities
+            .iter()
+            .map(|r| {
+                self.rarity_weights
+                    .iter()
+                    .find(|(rarity, _)| rarity == r)
+                    .map_or(0.0, |(_, w)| *w)
+            })
+            .collect();
+        let rarity = rarities[rng.pick_weighted(&weights)?];
+
+        let candidates: Vec<&str> = items
+            .iter()
+            .filter(|(_, r)| *r == rarity)
+            .map(|(id, _)| *id)
+            .collect();
+        let i = (rng.next_u64() % candidates.len() as u64) as usize;
+        Some(candidates[i].to_string())
+    }
+}
+
+/// The weights of rarities when a table does not set them
+fn default_weights() -> Vec<(Rarity, f64)> {
+    vec![
+        (Rarity::Common, 60.0),
+        (Rarity::Uncommon, 25.0),
+        (Rarity::Rare, 10.0),
+        (Rarity::Epic, 4.0),
+        (Rarity::Legendary, 0.9),
+        (Rarity::Mythic, 0.1),
+    ]
+}
+
+/// Read a range of floors like `1-9`, `5` or `10-`
+fn parse_floors(value: &str) -> Option<Source> {
+    match value.split_once('-') {
+        Some((min, "")) => Some(Source::Floors(min.trim().parse().ok()?, None)),
+        Some((min, max)) => Some(Source::Floors(
+            min.trim().parse().ok()?,
+            Some(max.trim().parse().ok()?),
+        )),
+        None => {
+            let floor = value.trim().parse().ok()?;
+            Some(Source::Floors(floor, Some(floor)))
+        }
+    }
+}
+
+/// Load the loot tables, giving every problem in the loot file if there are any.
+/// The enemies and items have to be loaded first, because the tables are checked against them.
+pub fn load() -> Result<(), Vec<String>> {
+    let _ = TABLES.set(load_sections(LOOT_FILE, BUILT_IN, LootTable::parse)?);
+    Ok(())
+}
+
+/// Get all loot tables, which are empty until they are loaded
+pub fn tables() -> &'static [LootTable] {
+    TABLES.get().map_or(&[], |tables| tables.as_slice())
+}
+
+/// Roll every loot table for an enemy that died on a floor, giving the ids of the items that dropped
+pub fn roll(enemy: &str, floor: u32, rng: &mut Rng) -> Vec<String> {
+    tables()
+        .iter()
+        .filter(|t| t.applies(enemy, floor))
+        .filter_map(|t| t.roll(rng))
+        .collect()
+}
@@ -0,0 +1,125 @@
+use crossterm::event::KeyCode;
+use enum_iterator::{all, Sequence};
+
+use crate::state::State;
+
+/// Attack of a heavy strike, compared to a normal attack
+pub const HEAVY_STRIKE_POWER: f64 = 2.5;
+/// Part of the max health a heal restores right away
+pub const HEAL_AMOUNT: f64 = 0.3;
+/// Part of the max health regenerated every second after a heal, and for how many seconds
+pub const HEAL_REGEN: f64 = 0.02;
+pub const HEAL_REGEN_DURATION: f32 = 5.0;
+/// Seconds a shield bash stuns the enemy
+pub const SHIELD_BASH_STUN: f32 = 1.5;
+/// Damage the shield of a shield bash absorbs, compared to the attack of the player, and for how many seconds
+pub const SHIELD_BASH_SHIELD: f64 = 2.0;
+pub const SHIELD_BASH_SHIELD_DURATION: f32 = 5.0;
+/// Number of attacks of a whirlwind, and the attack of each compared to a normal attack
+pub const WHIRLWIND_HITS: u32 = 3;
+pub const WHIRLWIND_POWER: f64 = 0.6;
+
+/// The skills the player can use in a fight, next to the normal attack
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum Skill {
+    /// A strong attack
+    HeavyStrike,
+    /// Heal part of the max health, and regenerate for a while
+    Heal,
+    /// A normal attack that stuns the enemy, and raises a shield
+    ShieldBash,
+    /// A flurry of weaker attacks
+    Whirlwind,
+}
+
+/// What a skill costs to use
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Cost {
+    Mana(f64),
+    Gold(u64),
+}
+
+/// How a skill is unlocked
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Unlock {
+    /// Reaching a level
+    Level(u32),
+    /// Buying the upgrade with this id in the shop
+    Shop(&'static str),
+}
+
+impl Skill {
+    /// The key that uses this skill
+    pub fn key(self) -> char {
+        match self {
+            Skill::HeavyStrike => '1',
+            Skill::Heal => '2',
+            Skill::ShieldBash => '3',
+            Skill::Whirlwind => '4',
+        }
+    }
+
+    /// Get the skill bound to a key
+    pub fn from_key(key: KeyCode) -> Option<Skill> {
+        all::<Skill>().find(|s| key == KeyCode::Char(s.key()))
+    }
+
+    /// The key of the name of this skill in the language files
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Skill::HeavyStrike => "skill.heavy_strike",
+            Skill::Heal => "skill.heal",
+            Skill::ShieldBash => "skill.shield_bash",
+            Skill::Whirlwind => "skill.whirlwind",
+        }
+    }
+
+    /// Seconds before the skill can be used again
+    pub fn cooldown(self) -> f32 {
+        match self {
+            Skill::HeavyStrike => 8.0,
+            Skill::Heal => 15.0,
+            Skill::ShieldBash => 12.0,
+            Skill::Whirlwind => 20.0,
+        }
+    }
+
+    pub fn cost(self) -> Cost {
+        match self {
+            Skill::HeavyStrike => Cost::Mana(3.0),
+            Skill::Heal => Cost::Mana(5.0),
+            Skill::ShieldBash => Cost::Gold(5),
+            Skill::Whirlwind => Cost::Gold(20),
+        }
+    }
+
+    pub fn unlock(self) -> Unlock {
+        match self {
+            Skill::HeavyStrike => Unlock::Level(2),
+            Skill::Heal => Unlock::Level(3),
+            Skill::ShieldBash => Unlock::Shop("ShieldBash"),
+            Skill::Whirlwind => Unlock::Shop("Whirlwind"),
+        }
+    }
+
+    /// Check if the skill is unlocked
+    pub fn is_unlocked(self, state: &State) -> bool {
+        match self.unlock() {
+            Unlock::Level(level) => state.fight.level >= level,
+            Unlock::Shop(id) => state.upgrades.contains(id).is_some(),
+        }
+    }
+
+    /// Check if the player has enough mana or gold to use the skill
+    pub fn can_pay(self, state: &State) -> bool {
+        match self.cost() {
+            Cost::Mana(mana) => state.fight.mana >= mana,
+            Cost::Gold(gold) => state.inventory.get_amount("Gold") >= gold,
+        }
+    }
+
+    /// Check if the skill needs an enemy to be used on
+    pub fn needs_enemy(self) -> bool {
+        self != Skill::Heal
+    }
+}
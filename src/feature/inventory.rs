@@ -1,20 +1,30 @@
 use std::default;
 
-use crossterm::{
-    event::KeyCode,
-    style::{StyledContent, Stylize},
-};
-use enum_iterator::Sequence;
+use crossterm::event::KeyCode;
+use enum_iterator::{all, cardinality, Sequence};
 
 use crate::{
-    feature::Feature,
+    feature::{
+        fight::equipment::{self, EquipError, Slot},
+        Feature,
+    },
+    locale::{t, tf},
+    message,
     state::State,
     util::{
         flag::{Flag, Flags},
-        style::rarity_stylize,
+        layout::content_size,
+        list::List,
+        markup::{escape, parse},
+        number::BigNum,
+        style::rarity_tag,
+        text::Line,
     },
 };
 
+/// Lines above the item list: one for every gear slot, and an empty line
+const GEAR_LINES: usize = cardinality::<Slot>() + 1;
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
 pub enum Rarity {
     Common,
@@ -25,13 +35,28 @@ pub enum Rarity {
     Mythic,
 }
 
+impl Rarity {
+    /// Get a rarity by its name, ignoring case
+    pub fn from_name(name: &str) -> Option<Rarity> {
+        all::<Rarity>().find(|r| format!("{:?}", r).eq_ignore_ascii_case(name.trim()))
+    }
+}
+
 pub struct Item {
+    pub id: String,
     pub name: String,
     pub description: String,
-    pub amount: u64,
+    pub amount: BigNum,
     pub rarity: Rarity,
 }
 
+impl Item {
+    /// The name of this item as markup, styled by its rarity
+    pub fn styled_name(&self) -> String {
+        format!("{{{}}}{}{{/}}", rarity_tag(self.rarity), escape(&self.name))
+    }
+}
+
 pub struct Inventory {
     items: Vec<Item>,
     cur_size: u32,
@@ -49,36 +74,46 @@ impl Default for Inventory {
 }
 
 impl Inventory {
-    pub fn add(&mut self, name: &str, amount: u64) {
-        if let Some(item) = get_item(name) {
-            if let Some(existing_item) = self.items.iter_mut().find(|i| i.name == name) {
-                existing_item.amount += amount;
-            } else {
-                if self.cur_size + 1 <= self.max_size {
-                    self.cur_size += 1;
-                    self.items.push(item);
-                    self.add(name, amount);
-                }
+    /// Add an amount of an item.
+    /// Returns false if the item is unknown, or if it is new and the inventory is full.
+    pub fn add(&mut self, id: &str, amount: BigNum) -> bool {
+        if let Some(existing_item) = self.items.iter_mut().find(|i| i.id == id) {
+            existing_item.amount += amount;
+            return true;
+        }
+
+        match get_item(id) {
+            Some(mut item) if self.cur_size < self.max_size => {
+                item.amount = amount;
+                self.cur_size += 1;
+                self.items.push(item);
+                true
             }
+            _ => false,
         }
     }
 
-    pub fn remove(&mut self, name: &str, amount: u64) {
-        if let Some(existing_item) = self.items.iter_mut().find(|i| i.name == name) {
+    pub fn remove(&mut self, id: &str, amount: BigNum) {
+        if let Some(existing_item) = self.items.iter_mut().find(|i| i.id == id) {
             existing_item.amount -= amount;
-            if existing_item.amount <= 0 {
-                self.items.retain(|i| i.name != name);
+            if existing_item.amount == 0 {
+                self.items.retain(|i| i.id != id);
                 self.cur_size -= 1;
             }
         }
     }
 
+    /// Make room for more different items
+    pub fn add_slots(&mut self, slots: u32) {
+        self.max_size += slots;
+    }
+
     /// Get the amount of an item in the inventory. or default 0
-    pub fn get_amount(&self, name: &str) -> u64 {
-        if let Some(item) = self.items.iter().find(|i| i.name == name) {
+    pub fn get_amount(&self, id: &str) -> BigNum {
+        if let Some(item) = self.items.iter().find(|i| i.id == id) {
             item.amount
         } else {
-            0
+            BigNum::ZERO
         }
     }
 }
@@ -94,12 +129,14 @@ impl Flag<State> for InventoryFlag {
 
 pub struct InventoryFeature {
     flags: Flags<InventoryFlag, State>,
+    list: List,
 }
 
 impl default::Default for InventoryFeature {
     fn default() -> Self {
         Self {
             flags: Flags::new(),
+            list: List::default(),
         }
     }
 }
@@ -108,83 +145,199 @@ impl Feature for InventoryFeature {
     fn get_info(&self) -> super::FeatureInfo {
         super::FeatureInfo {
             key: KeyCode::Char('i'),
-            name: "Inventory".white().bold(),
-            description: "View your inventory".italic().white(),
+            name: t("feature.inventory.name"),
+            description: t("feature.inventory.description"),
             visible_count: 5,
             unlock_count: 100,
-            counter_string: Some(" and more than 1 gold".stylize()),
+            counter_string: Some(t("feature.inventory.condition")),
         }
     }
 
-    fn get_top_bar(&self, state: &State) -> Vec<StyledContent<String>> {
-        vec![
-            format!(
-                " {}/{} | ",
-                state.inventory.items.len(),
-                state.inventory.max_size
-            )
-            .bold(),
-            "[d]Details ".to_string().stylize(),
-        ]
+    fn get_top_bar(&self, state: &State) -> Line {
+        parse(&tf(
+            "inventory.top_bar",
+            &[
+                ("count", &state.inventory.items.len()),
+                ("max", &state.inventory.max_size),
+            ],
+        ))
     }
 
     fn is_unlocked(&self, state: &State) -> bool {
-        state.inventory.items.len() > 0
+        !state.inventory.items.is_empty()
     }
 
-    fn update(&mut self, _: f32, state: &mut State, _: &mut crate::message::MessageManager) {
-        process_input(self, state.key, state);
+    fn update(&mut self, _: f32, state: &mut State, message: &mut message::MessageManager) {
+        process_input(self, state.key, state, message);
     }
 
-    fn render(&self, state: &State, _: &Vec<Box<dyn Feature>>) -> Vec<StyledContent<String>> {
-        state
+    fn render(&self, state: &State, _: &[Box<dyn Feature>]) -> Vec<Line> {
+        let mut gear: Vec<Line> = all::<Slot>()
+            .map(|slot| {
+                let item = match state.fight.gear.get(slot).and_then(get_item) {
+                    Some(item) => format!("{} {}", item.styled_name(), stats(&item.id)),
+                    None => t("inventory.gear.empty").to_string(),
+                };
+                parse(&tf(
+                    "inventory.gear",
+                    &[
+                        ("key", &slot.key()),
+                        ("slot", &t(slot.name_key())),
+                        ("item", &item),
+                    ],
+                ))
+            })
+            .collect();
+        gear.push(Line::default());
+
+        let lines = state
             .inventory
             .items
             .iter()
             .map(|item| {
-                format!(
-                    "[{}] {} {}",
-                    item.amount,
-                    rarity_stylize(item.rarity, item.name.as_str()),
-                    if self.flags.is_marked(&InventoryFlag::ShowDetailed) {
-                        item.description.as_str()
-                    } else {
-                        ""
-                    }
-                )
-                .stylize()
+                parse(&tf(
+                    "inventory.item",
+                    &[
+                        ("amount", &item.amount),
+                        ("name", &item.styled_name()),
+                        (
+                            "description",
+                            &if self.flags.is_marked(&InventoryFlag::ShowDetailed) {
+                                format!("{} {}", item.description, stats(&item.id))
+                            } else {
+                                String::new()
+                            },
+                        ),
+                    ],
+                ))
             })
-            .collect()
+            .collect();
+
+        let (width, height) = content_size();
+        let height = (height as usize).saturating_sub(gear.len());
+        gear.extend(self.list.render(lines, width, height));
+        gear
+    }
+
+    fn render_compact(&self, state: &State) -> Option<Vec<Line>> {
+        Some(vec![
+            parse(&tf(
+                "inventory.compact.gold",
+                &[("gold", &state.inventory.get_amount("Gold"))],
+            )),
+            parse(&tf(
+                "inventory.compact.items",
+                &[
+                    ("count", &state.inventory.items.len()),
+                    ("max", &state.inventory.max_size),
+                ],
+            )),
+        ])
     }
 }
 
-fn process_input(inv: &mut InventoryFeature, key: KeyCode, _state: &mut State) {
-    match key {
-        KeyCode::Char('d') => {
-            if inv.flags.is_marked(&InventoryFlag::ShowDetailed) {
-                inv.flags.unmark(InventoryFlag::ShowDetailed)
-            } else {
-                inv.flags.mark(InventoryFlag::ShowDetailed)
+fn process_input(
+    inv: &mut InventoryFeature,
+    key: KeyCode,
+    state: &mut State,
+    message: &mut message::MessageManager,
+) {
+    let height = (content_size().1 as usize).saturating_sub(GEAR_LINES);
+    if let Some(i) = inv
+        .list
+        .process_input(key, state.inventory.items.len(), height)
+    {
+        let item = &state.inventory.items[i];
+        message.add_message(message::Message {
+            text: parse(&tf(
+                "inventory.inspect",
+                &[
+                    ("name", &item.styled_name()),
+                    (
+                        "description",
+                        &format!("{} {}", item.description, stats(&item.id)),
+                    ),
+                ],
+            )),
+            location: message::TextLocation::BottomRight,
+            duration: 3.0,
+            category: message::MessageCategory::Inventory,
+            priority: message::Priority::Info,
+        });
+    }
+
+    let result = match key {
+        KeyCode::Char('e') => Some(match state.inventory.items.get(inv.list.cursor()) {
+            Some(item) => {
+                let id = item.id.clone();
+                equipment::equip(state, &id).map(|_| ("inventory.equipped", id))
             }
+            None => Err(EquipError::Missing),
+        }),
+        KeyCode::Char(c) => all::<Slot>()
+            .find(|s| s.key() == c)
+            .map(|slot| equipment::unequip(state, slot).map(|id| ("inventory.unequipped", id))),
+        _ => None,
+    };
+    let text = match result {
+        Some(Ok((key, id))) => get_item(&id).map(|item| tf(key, &[("name", &item.styled_name())])),
+        Some(Err(EquipError::NotGear)) => Some(t("inventory.not_gear").to_string()),
+        Some(Err(EquipError::Missing)) => Some(t("inventory.missing").to_string()),
+        Some(Err(EquipError::InventoryFull)) => Some(t("inventory.full").to_string()),
+        Some(Err(EquipError::Empty)) => Some(t("inventory.slot_empty").to_string()),
+        None => None,
+    };
+    if let Some(text) = text {
+        message.add_message(message::Message {
+            text: parse(&text),
+            location: message::TextLocation::BottomRight,
+            duration: 3.0,
+            category: message::MessageCategory::Inventory,
+            priority: message::Priority::Info,
+        });
+    }
+
+    if key == KeyCode::Char('d') {
+        if inv.flags.is_marked(&InventoryFlag::ShowDetailed) {
+            inv.flags.unmark(InventoryFlag::ShowDetailed)
+        } else {
+            inv.flags.mark(InventoryFlag::ShowDetailed)
         }
-        _ => {}
     }
 }
 
-fn get_item(name: &str) -> Option<Item> {
-    match name {
+/// Describe the stats an item adds when it is equipped, or nothing if it is not gear
+fn stats(id: &str) -> String {
+    match items::items().iter().find(|i| i.id == id) {
+        Some(item) if item.slot.is_some() => tf(
+            "inventory.stats",
+            &[("stats", &equipment::describe(&item.stats))],
+        ),
+        _ => String::new(),
+    }
+}
+
+pub fn get_item(id: &str) -> Option<Item> {
+    match id {
         "Gold" => Some(Item {
-            name: "Gold".to_string(),
-            description: "A shiny coin".to_string(),
-            amount: 0,
+            id: "Gold".to_string(),
+            name: t("item.gold.name").to_string(),
+            description: t("item.gold.description").to_string(),
+            amount: BigNum::ZERO,
             rarity: Rarity::Common,
         }),
         "XP" => Some(Item {
-            name: "XP".to_string(),
-            description: "Experience Points".to_string(),
-            amount: 0,
+            id: "XP".to_string(),
+            name: t("item.xp.name").to_string(),
+            description: t("item.xp.description").to_string(),
+            amount: BigNum::ZERO,
             rarity: Rarity::Uncommon,
         }),
-        _ => None,
+        _ => items::items()
+            .iter()
+            .find(|i| i.id == id)
+            .map(items::ItemType::create),
     }
 }
+
+pub mod items;
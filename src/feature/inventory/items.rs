@@ -0,0 +1,111 @@
+use std::sync::OnceLock;
+
+use crate::{
+    feature::fight::{equipment::Slot, Stats},
+    locale::t,
+    util::{
+        config::{load_sections, parse_value},
+        number::BigNum,
+    },
+};
+
+use super::{Item, Rarity};
+
+/// The file items are read from
+const ITEMS_FILE: &str = "data/items.cfg";
+const BUILT_IN: &str = include_str!("../../../data/items.cfg");
+
+static ITEMS: OnceLock<Vec<ItemType>> = OnceLock::new();
+
+/// The definition of a kind of item, as read from the item file
+#[derive(Clone, Debug)]
+pub struct ItemType {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub rarity: Rarity,
+    /// The slot this item can be equipped in, if it is gear
+    pub slot: Option<Slot>,
+    /// The stats this item adds while it is equipped
+    pub stats: Stats,
+}
+
+impl ItemType {
+    /// Read an item type from its section in the item file, with defaults for missing values.
+    /// Gives every unknown key and invalid value if there are any.
+    fn parse(id: &str, pairs: &[(&str, &str)]) -> Result<ItemType, Vec<String>> {
+        let mut item = ItemType {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            rarity: Rarity::Common,
+            slot: None,
+            stats: Stats::default(),
+        };
+
+        let mut errors = Vec::new();
+        for (key, value) in pairs {
+            let result = match *key {
+                "name" => {
+                    item.name = value.to_string();
+                    Ok(())
+                }
+                "description" => {
+                    item.description = value.to_string();
+                    Ok(())
+                }
+                "rarity" => Rarity::from_name(value)
+                    .map(|r| item.rarity = r)
+                    .ok_or_else(|| format!("`{}` is not a rarity", value)),
+                "slot" => Slot::from_name(value)
+                    .map(|s| item.slot = Some(s))
+                    .ok_or_else(|| format!("`{}` is not a slot", value)),
+                "attack" => parse_value(key, value).map(|v| item.stats.attack = v),
+                "defense" => parse_value(key, value).map(|v| item.stats.defense = v),
+                "max_health" => parse_value(key, value).map(|v| item.stats.max_health = v),
+                "attack_time" => parse_value(key, value).map(|v| item.stats.attack_time = v),
+                "regen" => parse_value(key, value).map(|v| item.stats.regen = v),
+                _ => Err(format!("unknown key `{}`", key)),
+            };
+            if let Err(error) = result {
+                errors.push(error);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(item)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Create an empty stack of this item, with its translated name and description
+    pub fn create(&self) -> Item {
+        Item {
+            id: self.id.clone(),
+            name: translate(&format!("item.{}.name", self.id), &self.name),
+            description: translate(&format!("item.{}.description", self.id), &self.description),
+            amount: BigNum::ZERO,
+            rarity: self.rarity,
+        }
+    }
+}
+
+/// Get the translation of a key, or a default if there is none
+fn translate(key: &str, default: &str) -> String {
+    match t(key) {
+        text if text == key => default.to_string(),
+        text => text.to_string(),
+    }
+}
+
+/// Load the item types, giving every problem in the item file if there are any
+pub fn load() -> Result<(), Vec<String>> {
+    let _ = ITEMS.set(load_sections(ITEMS_FILE, BUILT_IN, ItemType::parse)?);
+    Ok(())
+}
+
+/// Get all item types, which are empty until they are loaded
+pub fn items() -> &'static [ItemType] {
+    ITEMS.get().map_or(&[], |items| items.as_slice())
+}
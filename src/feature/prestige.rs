@@ -0,0 +1,231 @@
+use crossterm::event::KeyCode;
+use enum_iterator::{all, Sequence};
+
+use crate::{
+    feature::{
+        counter::{Generators, Milestones},
+        fight::FightData,
+        inventory::Inventory,
+        shop::Upgrades,
+        Feature,
+    },
+    locale::{t, tf},
+    message::{self, Message, Prompt},
+    state::State,
+    util::{layout::content_size, list::List, markup::parse, number::BigNum, text::Line},
+};
+
+/// The count needed before prestige points can be gained from it
+const COUNT_PER_POINT: f64 = 1000.0;
+/// The floors needed for every prestige point from fighting
+const FLOORS_PER_POINT: u32 = 5;
+
+/// Prestige feature
+/// A feature that resets all progress in exchange for prestige points.
+/// Points are spent on bonuses that are kept across resets.
+#[derive(Default)]
+pub struct PrestigeFeature {
+    list: List,
+}
+
+/// Permanent bonuses that can be bought with prestige points
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum Bonus {
+    Count,
+    Gold,
+    Xp,
+}
+
+impl Bonus {
+    /// The translated name of this bonus
+    pub fn name(&self) -> &'static str {
+        match self {
+            Bonus::Count => t("bonus.count.name"),
+            Bonus::Gold => t("bonus.gold.name"),
+            Bonus::Xp => t("bonus.xp.name"),
+        }
+    }
+
+    /// The translated description of this bonus
+    pub fn description(&self) -> &'static str {
+        match self {
+            Bonus::Count => t("bonus.count.description"),
+            Bonus::Gold => t("bonus.gold.description"),
+            Bonus::Xp => t("bonus.xp.description"),
+        }
+    }
+
+    /// The cost of the next level of this bonus, when it is at `level`
+    pub fn cost(&self, level: u32) -> BigNum {
+        BigNum::from(2.0).pow(level as f64)
+    }
+}
+
+/// Progress that is kept when the game is reset
+#[derive(Default)]
+pub struct Prestige {
+    pub points: BigNum,
+    pub resets: u32,
+    levels: Vec<(Bonus, u32)>,
+}
+
+impl Prestige {
+    /// Get the level of a bonus
+    pub fn level(&self, bonus: Bonus) -> u32 {
+        self.levels
+            .iter()
+            .find(|(b, _)| *b == bonus)
+            .map_or(0, |(_, level)| *level)
+    }
+
+    /// Get the multiplier of a bonus: every level adds half of the base amount
+    pub fn multiplier(&self, bonus: Bonus) -> f64 {
+        1.0 + 0.5 * self.level(bonus) as f64
+    }
+
+    fn buy(&mut self, bonus: Bonus) {
+        if let Some((_, level)) = self.levels.iter_mut().find(|(b, _)| *b == bonus) {
+            *level += 1;
+        } else {
+            self.levels.push((bonus, 1));
+        }
+    }
+}
+
+/// The prestige points a reset would give right now, from the count and the highest floor
+pub fn gain(state: &State) -> BigNum {
+    (state.count / COUNT_PER_POINT).pow(0.5).floor()
+        + BigNum::from(state.fight.max_floor / FLOORS_PER_POINT)
+}
+
+impl Feature for PrestigeFeature {
+    fn get_info(&self) -> super::FeatureInfo {
+        super::FeatureInfo {
+            key: KeyCode::Char('p'),
+            name: t("feature.prestige.name"),
+            description: t("feature.prestige.description"),
+            visible_count: 500,
+            unlock_count: 1000,
+            counter_string: None,
+        }
+    }
+
+    fn get_top_bar(&self, state: &State) -> Line {
+        parse(&tf(
+            "prestige.top_bar",
+            &[("points", &state.prestige.points)],
+        ))
+    }
+
+    /// Once the player has reset, bonuses can always be bought
+    fn is_available(&self, state: &State) -> bool {
+        state.prestige.resets > 0 || state.count >= self.get_info().unlock_count
+    }
+
+    fn update(&mut self, _: f32, state: &mut State, message: &mut message::MessageManager) {
+        if state.key == KeyCode::Char('r') {
+            let gain = gain(state);
+            if gain > 0 {
+                message.add_prompt(Prompt {
+                    text: parse(&tf("prestige.confirm", &[("gain", &gain)])),
+                    choices: vec!['y', 'n'],
+                    category: message::MessageCategory::Prestige,
+                    callback: Box::new(|choice, state, message| {
+                        if choice == 'y' {
+                            reset(state, message);
+                        }
+                    }),
+                });
+            } else {
+                message.add_message(Message {
+                    text: parse(t("prestige.no_gain")),
+                    location: message::TextLocation::BottomRight,
+                    duration: 3.0,
+                    category: message::MessageCategory::Prestige,
+                    priority: message::Priority::Info,
+                });
+            }
+        }
+
+        let bonuses: Vec<Bonus> = all::<Bonus>().collect();
+        let height = content_size().1 as usize;
+        if let Some(i) = self.list.process_input(state.key, bonuses.len(), height) {
+            buy(bonuses[i], state, message);
+        }
+    }
+
+    fn render(&self, state: &State, _: &[Box<dyn Feature>]) -> Vec<Line> {
+        let mut lines = vec![
+            parse(&tf(
+                "prestige.points",
+                &[("points", &state.prestige.points)],
+            )),
+            parse(&tf("prestige.preview", &[("gain", &gain(state))])),
+            Line::default(),
+        ];
+
+        let bonuses = all::<Bonus>()
+            .map(|b| {
+                let level = state.prestige.level(b);
+                parse(&tf(
+                    "prestige.bonus",
+                    &[
+                        ("level", &level),
+                        ("cost", &b.cost(level)),
+                        ("name", &b.name()),
+                        (
+                            "multiplier",
+                            &format!("{:.1}", state.prestige.multiplier(b)),
+                        ),
+                        ("description", &b.description()),
+                    ],
+                ))
+            })
+            .collect();
+
+        let (width, height) = content_size();
+        let height = (height as usize).saturating_sub(lines.len());
+        lines.append(&mut self.list.render(bonuses, width, height));
+        lines
+    }
+}
+
+/// Buy a level of a bonus if the player has enough prestige points for it
+fn buy(bonus: Bonus, state: &mut State, message: &mut message::MessageManager) {
+    let cost = bonus.cost(state.prestige.level(bonus));
+    if state.prestige.points >= cost {
+        state.prestige.points -= cost;
+        state.prestige.buy(bonus);
+    } else {
+        message.add_message(Message {
+            text: parse(t("prestige.not_enough")),
+            location: message::TextLocation::BottomRight,
+            duration: 2.0,
+            category: message::MessageCategory::Prestige,
+            priority: message::Priority::Info,
+        });
+    }
+}
+
+/// Reset all progress, except the prestige itself, and give the prestige points for it
+fn reset(state: &mut State, message: &mut message::MessageManager) {
+    let gain = gain(state);
+    state.prestige.points += gain;
+    state.prestige.resets += 1;
+
+    state.count = BigNum::ZERO;
+    state.generators = Generators::default();
+    state.milestones = Milestones::default();
+    state.fight = FightData::default();
+    state.inventory = Inventory::default();
+    state.upgrades = Upgrades::default();
+    state.selected_feature = None;
+
+    message.add_message(Message {
+        text: parse(&tf("prestige.done", &[("gain", &gain)])),
+        location: message::TextLocation::Center,
+        duration: 5.0,
+        category: message::MessageCategory::Prestige,
+        priority: message::Priority::Critical,
+    });
+}
@@ -1,120 +1,213 @@
-use crossterm::{event::KeyCode, style::Stylize};
+use crossterm::event::KeyCode;
 
-use super::{inventory::Rarity, Feature};
+use super::{fight, inventory::Rarity, Feature};
 use crate::{
-    message::Message,
-    util::{conv::to_number, style::rarity_stylize},
+    locale::{t, tf},
+    message::{Message, Prompt},
+    util::{
+        layout::content_size,
+        list::List,
+        markup::{escape, parse},
+        number::BigNum,
+        rng::Stream,
+        style::rarity_tag,
+        text::Line,
+    },
     *,
 };
 
+/// The part of the cost that is paid for the upgrade on sale
+const SALE_PRICE: f64 = 0.75;
+
 pub struct Upgrade {
+    pub id: String,
     pub name: String,
     pub description: String,
-    pub cost: u64,
+    pub cost: BigNum,
     pub max_count: u32,
     pub rarity: Rarity,
     pub apply: Box<dyn Fn(&mut State)>,
 }
 
+impl Upgrade {
+    /// The name of this upgrade as markup, styled by its rarity
+    pub fn styled_name(&self) -> String {
+        format!("{{{}}}{}{{/}}", rarity_tag(self.rarity), escape(&self.name))
+    }
+
+    /// The cost of this upgrade, lowered if it is on sale
+    pub fn price(&self, state: &State) -> BigNum {
+        if state.upgrades.on_sale(&self.id) {
+            self.cost * SALE_PRICE
+        } else {
+            self.cost
+        }
+    }
+
+    /// The name of this upgrade as markup, marked if it is on sale
+    fn offer_name(&self, state: &State) -> String {
+        if state.upgrades.on_sale(&self.id) {
+            format!("{} {}", self.styled_name(), t("shop.sale"))
+        } else {
+            self.styled_name()
+        }
+    }
+}
+
 pub fn get_all_upgrades() -> Vec<Upgrade> {
     vec![
         Upgrade {
-            name: "Damage".to_string(),
-            description: "Increases your damage by 1".to_string(),
-            cost: 5,
+            id: "Damage".to_string(),
+            name: t("upgrade.damage.name").to_string(),
+            description: t("upgrade.damage.description").to_string(),
+            cost: BigNum::from(5.0),
             max_count: 5,
             rarity: Rarity::Common,
             apply: Box::new(|state| {
-                state.fight.player.attack += 1.0;
+                state.fight.base.attack += 1.0;
+                state.fight.update_stats();
             }),
         },
         Upgrade {
-            name: "Health".to_string(),
-            description: "Increases your max health by 1".to_string(),
-            cost: 5,
+            id: "Health".to_string(),
+            name: t("upgrade.health.name").to_string(),
+            description: t("upgrade.health.description").to_string(),
+            cost: BigNum::from(5.0),
             max_count: 5,
             rarity: Rarity::Common,
             apply: Box::new(|state| {
-                state.fight.player.max_health += 1.0;
+                state.fight.base.max_health += 1.0;
+                state.fight.update_stats();
             }),
         },
         Upgrade {
-            name: "Regeneration".to_string(),
-            description: "Doubles your health regeneration at floor 0".to_string(),
-            cost: 10,
+            id: "Regeneration".to_string(),
+            name: t("upgrade.regeneration.name").to_string(),
+            description: t("upgrade.regeneration.description").to_string(),
+            cost: BigNum::from(10.0),
             max_count: 1,
             rarity: Rarity::Uncommon,
             apply: Box::new(|state| {
-                state.fight.regen += 0.5;
+                state.fight.base.regen += 0.5;
+                state.fight.update_stats();
             }),
         },
+        Upgrade {
+            id: "ShieldBash".to_string(),
+            name: t("upgrade.shield_bash.name").to_string(),
+            description: t("upgrade.shield_bash.description").to_string(),
+            cost: BigNum::from(30.0),
+            max_count: 1,
+            rarity: Rarity::Rare,
+            // unlocks a skill, see `Skill::is_unlocked`
+            apply: Box::new(|_| {}),
+        },
+        Upgrade {
+            id: "Whirlwind".to_string(),
+            name: t("upgrade.whirlwind.name").to_string(),
+            description: t("upgrade.whirlwind.description").to_string(),
+            cost: BigNum::from(75.0),
+            max_count: 1,
+            rarity: Rarity::Epic,
+            apply: Box::new(|_| {}),
+        },
+        Upgrade {
+            id: fight::auto::UPGRADE.to_string(),
+            name: t("upgrade.auto_battle.name").to_string(),
+            description: t("upgrade.auto_battle.description").to_string(),
+            cost: BigNum::from(50.0),
+            max_count: 1,
+            rarity: Rarity::Rare,
+            apply: Box::new(|_| {}),
+        },
     ]
 }
 
+/// Get all upgrades that can still be bought
+fn get_available_upgrades(state: &State) -> Vec<Upgrade> {
+    get_all_upgrades()
+        .into_iter()
+        .filter(|u| {
+            state
+                .upgrades
+                .contains(u.id.as_str())
+                .map_or_else(|| true, |c| c < u.max_count)
+        })
+        .collect()
+}
+
+/// Put a random upgrade that can still be bought on sale, rolled from the shop stream
+fn roll_sale(state: &mut State) {
+    let upgrades = get_available_upgrades(state);
+    state.upgrades.sale = if upgrades.is_empty() {
+        None
+    } else {
+        let i = state.rng.stream(Stream::Shop).next_u64() % upgrades.len() as u64;
+        Some(upgrades[i as usize].id.clone())
+    };
+}
+
 pub struct UpgradeInfo {
-    pub name: String,
+    pub id: String,
     pub count: u32,
 }
 
+#[derive(Default)]
 pub struct Upgrades {
     upgrades: Vec<UpgradeInfo>,
+    /// The upgrade that is on sale, rolled again after every purchase
+    sale: Option<String>,
 }
 
 impl Upgrades {
-    pub fn contains(&self, name: &str) -> Option<u32> {
-        self.upgrades
-            .iter()
-            .find(|u| u.name == name)
-            .map(|u| u.count)
+    pub fn contains(&self, id: &str) -> Option<u32> {
+        self.upgrades.iter().find(|u| u.id == id).map(|u| u.count)
+    }
+
+    pub fn on_sale(&self, id: &str) -> bool {
+        self.sale.as_deref() == Some(id)
     }
 
-    pub fn buy(&mut self, name: &str) {
-        if let Some(u) = self.upgrades.iter_mut().find(|u| u.name == name) {
+    pub fn buy(&mut self, id: &str) {
+        if let Some(u) = self.upgrades.iter_mut().find(|u| u.id == id) {
             u.count += 1;
         } else {
             self.upgrades.push(UpgradeInfo {
-                name: name.to_string(),
+                id: id.to_string(),
                 count: 1,
             });
         }
     }
 }
 
-impl Default for Upgrades {
-    fn default() -> Self {
-        Self {
-            upgrades: Vec::new(),
-        }
-    }
+#[derive(Default)]
+pub struct ShopFeature {
+    list: List,
 }
 
-pub struct ShopFeature;
-
 impl Feature for ShopFeature {
     fn get_info(&self) -> super::FeatureInfo {
         super::FeatureInfo {
             key: KeyCode::Char('s'),
-            name: "Shop".yellow(),
-            description: "A shop where you can buy items to help you in your adventure".dark_grey(),
+            name: t("feature.shop.name"),
+            description: t("feature.shop.description"),
             visible_count: 50,
             unlock_count: 200,
             counter_string: None,
         }
     }
 
-    fn get_top_bar(
-        &self,
-        state: &crate::state::State,
-    ) -> Vec<crossterm::style::StyledContent<String>> {
-        vec![
-            " | Gold: ".to_string().stylize(),
-            state
-                .inventory
-                .get_amount("Gold")
-                .to_string()
-                .dark_yellow()
-                .bold(),
-        ]
+    fn get_top_bar(&self, state: &crate::state::State) -> Line {
+        parse(&tf(
+            "shop.top_bar",
+            &[("gold", &state.inventory.get_amount("Gold"))],
+        ))
+    }
+
+    fn tick(&mut self, _delta: f32, state: &mut State, _message: &mut message::MessageManager) {
+        if state.upgrades.sale.is_none() {
+            roll_sale(state);
+        }
     }
 
     fn update(
@@ -123,75 +216,103 @@ impl Feature for ShopFeature {
         state: &mut state::State,
         message: &mut message::MessageManager,
     ) {
-        if let Some(i) = to_number(state.key) {
-            if let Some(upgrade) = get_all_upgrades()
-                .iter()
-                .filter(|u| {
-                    state
-                        .upgrades
-                        .contains(u.name.as_str())
-                        .map_or_else(|| true, |c| c < u.max_count)
-                })
-                .nth(i - 1)
-            {
-                if state.inventory.get_amount("Gold") >= upgrade.cost {
-                    state.inventory.remove("Gold", upgrade.cost);
-                    state.upgrades.buy(upgrade.name.as_str());
-                    (upgrade.apply)(state);
-                    message.add_message(Message {
-                        text: format!(
-                            "You bought {} for {} gold. {}/{}",
-                            upgrade.name,
-                            upgrade.cost,
-                            state.upgrades.contains(upgrade.name.as_str()).unwrap_or(0),
-                            upgrade.max_count
-                        )
-                        .green(),
-                        location: message::TextLocation::Center,
-                        duration: 5.0,
-                    });
-                } else {
-                    message.add_message(Message {
-                        text: "You don't have enough gold".to_string().red(),
-                        location: message::TextLocation::Center,
-                        duration: 3.0,
-                    });
-                }
-            }
+        let upgrades = get_available_upgrades(state);
+        let height = content_size().1 as usize;
+        if let Some(i) = self.list.process_input(state.key, upgrades.len(), height) {
+            let upgrade = &upgrades[i];
+            let id = upgrade.id.clone();
+            message.add_prompt(Prompt {
+                text: parse(&tf(
+                    "shop.confirm",
+                    &[
+                        ("name", &upgrade.offer_name(state)),
+                        ("cost", &upgrade.price(state)),
+                    ],
+                )),
+                choices: vec!['y', 'n'],
+                category: message::MessageCategory::Shop,
+                callback: Box::new(move |choice, state, message| {
+                    if choice == 'y' {
+                        buy(&id, state, message);
+                    }
+                }),
+            });
         }
     }
 
-    fn render(
-        &self,
-        state: &state::State,
-        _features: &Vec<Box<dyn Feature>>,
-    ) -> Vec<crossterm::style::StyledContent<String>> {
-        let mut lines = Vec::new();
-
-        get_all_upgrades()
+    fn render(&self, state: &state::State, _features: &[Box<dyn Feature>]) -> Vec<Line> {
+        let lines = get_available_upgrades(state)
             .iter()
-            .filter(|u| {
-                state
-                    .upgrades
-                    .contains(u.name.as_str())
-                    .map_or_else(|| true, |c| c < u.max_count)
+            .map(|u| {
+                parse(&tf(
+                    "shop.upgrade",
+                    &[
+                        (
+                            "count",
+                            &state.upgrades.contains(u.id.as_str()).unwrap_or(0),
+                        ),
+                        ("max", &u.max_count),
+                        ("cost", &u.price(state)),
+                        ("name", &u.offer_name(state)),
+                        ("description", &u.description),
+                    ],
+                ))
             })
-            .take(10)
-            .enumerate()
-            .for_each(|(i, u)| {
-                lines.push(
-                    format!(
-                        "({}) |{}/{}| [{}] <{}> {}",
-                        i + 1,
-                        state.upgrades.contains(u.name.as_str()).unwrap_or(0),
-                        u.max_count,
-                        u.cost,
-                        rarity_stylize(u.rarity, u.name.as_str()),
-                        u.description
-                    )
-                    .stylize(),
-                );
+            .collect();
+
+        let (width, height) = content_size();
+        self.list.render(lines, width, height as usize)
+    }
+
+    fn render_compact(&self, state: &State) -> Option<Vec<Line>> {
+        Some(match get_available_upgrades(state).into_iter().next() {
+            Some(u) => vec![
+                parse(&tf(
+                    "shop.compact.offer",
+                    &[("name", &u.offer_name(state)), ("cost", &u.price(state))],
+                )),
+                parse(&u.description),
+            ],
+            None => vec![parse(t("shop.compact.sold_out"))],
+        })
+    }
+}
+
+/// Buy an upgrade if the player has enough gold for it
+fn buy(id: &str, state: &mut State, message: &mut message::MessageManager) {
+    if let Some(upgrade) = get_all_upgrades().iter().find(|u| u.id == id) {
+        let cost = upgrade.price(state);
+        if state.inventory.get_amount("Gold") >= cost {
+            state.inventory.remove("Gold", cost);
+            state.upgrades.buy(upgrade.id.as_str());
+            (upgrade.apply)(state);
+            roll_sale(state);
+            message.add_message(Message {
+                text: parse(&tf(
+                    "shop.bought",
+                    &[
+                        ("name", &upgrade.styled_name()),
+                        ("cost", &cost),
+                        (
+                            "count",
+                            &state.upgrades.contains(upgrade.id.as_str()).unwrap_or(0),
+                        ),
+                        ("max", &upgrade.max_count),
+                    ],
+                )),
+                location: message::TextLocation::BottomRight,
+                duration: 5.0,
+                category: message::MessageCategory::Shop,
+                priority: message::Priority::Info,
             });
-        lines
+        } else {
+            message.add_message(Message {
+                text: parse(t("shop.not_enough_gold")),
+                location: message::TextLocation::BottomRight,
+                duration: 3.0,
+                category: message::MessageCategory::Shop,
+                priority: message::Priority::Info,
+            });
+        }
     }
 }
@@ -0,0 +1,104 @@
+use std::{collections::HashMap, fmt::Display, fs, sync::OnceLock};
+
+use crate::util::{config::pairs, number::BigNum};
+
+/// The language used when a string is missing from the selected language
+const FALLBACK: &str = "en";
+
+/// Languages that are built into the game.
+/// A file in the `lang` folder with the same name takes precedence, so translations can be changed without rebuilding.
+const BUILT_IN: &[(&str, &str)] = &[
+    ("en", include_str!("../lang/en.lang")),
+    ("nl", include_str!("../lang/nl.lang")),
+];
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// A string table for a language, with the fallback language to look up missing strings
+struct Locale {
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+/// Select the language of all strings.
+/// This has to be called before any string is looked up, otherwise the fallback language is used.
+pub fn init(lang: &str) {
+    let _ = LOCALE.set(Locale {
+        strings: load(lang),
+        fallback: load(FALLBACK),
+    });
+}
+
+/// Load the string table of a language
+fn load(lang: &str) -> HashMap<String, String> {
+    let text = fs::read_to_string(format!("lang/{}.lang", lang))
+        .ok()
+        .or_else(|| {
+            BUILT_IN
+                .iter()
+                .find(|(l, _)| *l == lang)
+                .map(|(_, text)| text.to_string())
+        });
+
+    text.map_or_else(HashMap::new, |text| {
+        pairs(&text)
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    })
+}
+
+/// Get the selected locale
+fn locale() -> &'static Locale {
+    LOCALE.get_or_init(|| Locale {
+        strings: load(FALLBACK),
+        fallback: HashMap::new(),
+    })
+}
+
+/// Get the string for a key, falling back to English and then to the key itself
+pub fn t(key: &str) -> &str {
+    let locale = locale();
+    locale
+        .strings
+        .get(key)
+        .or_else(|| locale.fallback.get(key))
+        .map_or(key, |s| s.as_str())
+}
+
+/// Get the string for a key, replacing every `{$name}` placeholder with its argument
+pub fn tf(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    substitute(t(key), args)
+}
+
+/// Get the plural form of a string for a count, like `key.one` or `key.other`.
+/// The count is also available as the `{$count}` placeholder.
+pub fn tn(key: &str, count: BigNum, args: &[(&str, &dyn Display)]) -> String {
+    let form = format!("{}.{}", key, plural(count));
+    let other = format!("{}.other", key);
+    let text = if t(&form) != form {
+        t(&form)
+    } else {
+        t(&other)
+    };
+
+    let mut args = args.to_vec();
+    args.push(("count", &count));
+    substitute(text, &args)
+}
+
+/// Replace every `{$name}` placeholder with its argument
+fn substitute(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{${}}}", name), &value.to_string())
+    })
+}
+
+/// The plural form for a count.
+/// All languages so far (English and Dutch) only have a singular for exactly one.
+fn plural(count: BigNum) -> &'static str {
+    if count == 1 {
+        "one"
+    } else {
+        "other"
+    }
+}
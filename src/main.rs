@@ -1,6 +1,10 @@
 mod feature;
+mod locale;
 mod message;
+mod save;
+mod settings;
 mod state;
+mod theme;
 mod util;
 
 use std::{
@@ -12,23 +16,48 @@ use crossterm::{
     cursor::{DisableBlinking, EnableBlinking, Hide, MoveTo, MoveToNextLine, Show},
     event::{poll, Event, KeyCode},
     execute, queue,
-    style::{Print, Stylize},
+    style::Print,
     terminal::{
         disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
         LeaveAlternateScreen,
     },
 };
-use feature::{counter, exit, fight, inventory, shop, Feature};
+use feature::{character, counter, dashboard, exit, fight, inventory, prestige, shop, Feature};
+use locale::{t, tf};
+use settings::Settings;
 use state::State;
-use util::conv::get_string;
+use util::{
+    conv::{get_string, wrap_all},
+    layout::content_size,
+    markup::{escape, parse},
+    number::{self, BigNum},
+    rng::Random,
+};
 
 use crate::util::commands::{Divider, PrintAll, PrintAllLines};
 
 fn main() -> std::io::Result<()> {
+    let settings = Settings::load();
+    locale::init(&settings.lang);
+    theme::init(&settings.theme);
+    number::init(&settings.number_format);
+
+    // refuse to start with broken data files, before the terminal is taken over
+    if let Err(errors) = load_data() {
+        for error in errors {
+            eprintln!("{}", error);
+        }
+        std::process::exit(1);
+    }
+
     enable_raw_mode()?;
 
     let mut features = create_features();
     let mut state = create_state();
+    save::load(&mut state);
+    if let Some(seed) = settings.seed {
+        state.rng = Random::new(seed);
+    }
 
     let mut message_manager = message::MessageManager::default();
 
@@ -46,9 +75,18 @@ fn main() -> std::io::Result<()> {
 
     // render hello message
     message_manager.add_message(message::Message {
-        text: "Welcome to the game! First, go into the Counter feature.  You leave a feature with [q].".to_string().bold(),
+        text: parse(t("main.welcome")),
         location: message::TextLocation::Center,
         duration: 5.0,
+        category: message::MessageCategory::Tutorial,
+        priority: message::Priority::Info,
+    });
+    message_manager.add_message(message::Message {
+        text: parse(t("main.log_hint")),
+        location: message::TextLocation::Bottom,
+        duration: 5.0,
+        category: message::MessageCategory::Tutorial,
+        priority: message::Priority::Info,
     });
 
     // first time render
@@ -57,7 +95,7 @@ fn main() -> std::io::Result<()> {
     // simple game loop: process input, step, render
     loop {
         let key = wait_key(ms_step.into());
-        process_input(key, &features, &mut state);
+        process_input(key, &features, &mut state, &mut message_manager);
         step(
             ms_step as f32 / 1000.0,
             &mut features,
@@ -82,18 +120,30 @@ fn main() -> std::io::Result<()> {
         Show,
         LeaveAlternateScreen
     )?;
-    Ok(())
+
+    save::save(&state)
+}
+
+/// Load all data files, stopping at the first file with problems.
+/// Loot tables are checked against the enemies and items, so they are loaded last.
+fn load_data() -> Result<(), Vec<String>> {
+    fight::enemy::load()?;
+    inventory::items::load()?;
+    fight::loot::load()
 }
 
 /// Create all features
 fn create_features() -> Vec<Box<dyn Feature>> {
-    let mut features: Vec<Box<dyn Feature>> = Vec::new();
-    features.push(Box::new(exit::ExitFeature));
-    features.push(Box::new(counter::CounterFeature));
-    features.push(Box::new(fight::FightFeature::default()));
-    features.push(Box::new(inventory::InventoryFeature::default()));
-    features.push(Box::new(shop::ShopFeature));
-    features
+    vec![
+        Box::new(exit::ExitFeature),
+        Box::new(counter::CounterFeature::default()),
+        Box::new(fight::FightFeature::default()),
+        Box::new(character::CharacterFeature::default()),
+        Box::new(inventory::InventoryFeature::default()),
+        Box::new(shop::ShopFeature::default()),
+        Box::new(prestige::PrestigeFeature::default()),
+        Box::new(dashboard::DashboardFeature::default()),
+    ]
 }
 
 /// Create initial state
@@ -102,90 +152,155 @@ fn create_state() -> State {
         key: KeyCode::Null,
         selected_feature: None,
         quit: false,
+        rng: Random::from_time(),
 
-        count: 0,
+        count: BigNum::ZERO,
+        generators: counter::Generators::default(),
+        milestones: counter::Milestones::default(),
         fight: fight::FightData::default(),
         inventory: inventory::Inventory::default(),
         upgrades: shop::Upgrades::default(),
+        prestige: prestige::Prestige::default(),
     }
 }
 
 /// Process input
-fn process_input(key: KeyCode, features: &Vec<Box<dyn Feature>>, state: &mut State) {
-    if state.selected_feature.is_some() {
+fn process_input(
+    key: KeyCode,
+    features: &[Box<dyn Feature>],
+    state: &mut State,
+    message: &mut message::MessageManager,
+) {
+    if message.has_prompt() {
+        message.answer(key, state);
+        state.key = KeyCode::Null;
+    } else if message.log.open {
+        message.log.process_input(key, log_height());
+        state.key = KeyCode::Null;
+    } else if key == KeyCode::Tab {
+        message.log.open = true;
+        state.key = KeyCode::Null;
+    } else if state.selected_feature.is_some() {
         match key {
             KeyCode::Char('q') => state.selected_feature = None,
             k => state.key = k,
         }
+    } else if key == KeyCode::Char('n') {
+        number::next_format();
     } else {
-        match key {
-            k => {
-                state.selected_feature = features.iter().position(|f| {
-                    f.is_unlocked(state)
-                        && state.count >= f.get_info().unlock_count
-                        && f.get_info().key == k
-                })
-            }
-        }
+        state.selected_feature = features
+            .iter()
+            .position(|f| f.is_available(state) && f.get_info().key == key)
     }
 }
 
-/// Step the current selected feature
+/// Step all features in the background, and the current selected feature
 fn step(
     delta: f32,
-    features: &mut Vec<Box<dyn Feature>>,
+    features: &mut [Box<dyn Feature>],
     state: &mut State,
     message: &mut message::MessageManager,
 ) {
+    // messages are updated first, so a message added in response to Enter is not dismissed right away
+    message.update(state.key, delta);
+
+    for feature in features.iter_mut() {
+        feature.tick(delta, state, message);
+    }
+
     if let Some(i) = state.selected_feature {
         let feature = &mut features[i];
         feature.update(delta, state, message);
     }
-
-    message.update(state.key, delta);
 }
 
 /// Render the current selected feature, or the list of features
-fn render(features: &Vec<Box<dyn Feature>>, state: &State, message: &mut message::MessageManager) {
+fn render(features: &[Box<dyn Feature>], state: &State, message: &mut message::MessageManager) {
     let mut stdout = stdout();
+    let (t_c, _) = crossterm::terminal::size().unwrap_or((80, 24));
+
+    // render the message log on top of everything
+    if message.log.open {
+        queue!(
+            stdout,
+            Clear(ClearType::All),
+            MoveTo(0, 0),
+            PrintAll(vec![parse(t("log.title")), message.log.get_top_bar()]),
+            MoveToNextLine(1),
+            Divider('='),
+            PrintAllLines(message.log.render(log_height()))
+        )
+        .expect("Failed to render");
 
     // render the selected feature
-    if let Some(i) = state.selected_feature {
+    } else if let Some(i) = state.selected_feature {
         let feature = &features[i];
 
         queue!(
             stdout,
             Clear(ClearType::All),
             MoveTo(0, 0),
-            Print(feature.get_info().name),
-            PrintAll(feature.get_top_bar(state)),
+            PrintAll(vec![
+                parse(feature.get_info().name),
+                feature.get_top_bar(state)
+            ]),
             MoveToNextLine(1),
             Divider('='),
-            PrintAllLines(feature.render(state, features))
+            PrintAllLines(
+                wrap_all(feature.render(state, features), t_c)
+                    .into_iter()
+                    .take(log_height())
+                    .collect()
+            )
         )
         .expect("Failed to render");
 
     // or render the list of features
     } else {
         let mut str = String::new();
+        let mut descriptions = Vec::new();
         for feature in features {
             let info = feature.get_info();
 
-            if feature.is_unlocked(state) && state.count >= info.unlock_count {
-                str.push_str(&format!("[{}]{} ", get_string(info.key), info.name));
+            if feature.is_available(state) {
+                str.push_str(&format!(
+                    "[{}]{} ",
+                    escape(&get_string(info.key)),
+                    info.name
+                ));
+                descriptions.push(parse(&format!("{}: {}", info.name, info.description)));
             } else {
-                str.push_str(&format!("{} ", info.name.crossed_out()));
+                str.push_str(&format!("{{crossed}}{}{{/}} ", info.name));
             }
         }
+        str.push_str(t("main.log_key"));
+        str.push_str(&tf(
+            "main.number_key",
+            &[("format", &number::format().label())],
+        ));
 
-        queue!(stdout, Clear(ClearType::All), MoveTo(0, 0), Print(str)).expect("Failed to render");
+        queue!(
+            stdout,
+            Clear(ClearType::All),
+            MoveTo(0, 0),
+            Print(parse(&str)),
+            MoveToNextLine(1),
+            Divider('='),
+            PrintAllLines(wrap_all(descriptions, t_c))
+        )
+        .expect("Failed to render");
     }
 
-    message.render_one();
+    message.render();
 
     stdout.flush().expect("Failed to render");
 }
 
+/// Get the amount of lines available to the message log
+fn log_height() -> usize {
+    content_size().1 as usize
+}
+
 /// Wait for a key for a certain amount of time
 fn wait_key(ms: u128) -> KeyCode {
     let mut input = KeyCode::Null;
@@ -1,83 +1,299 @@
 use std::{collections::VecDeque, io::stdout};
 
-use crossterm::{
-    cursor::MoveTo,
-    event::KeyCode,
-    queue,
-    style::{Print, StyledContent},
+use crossterm::{event::KeyCode, queue, style::ContentStyle};
+use enum_iterator::{all, Sequence};
+
+use crate::{
+    locale::t,
+    state::State,
+    util::{commands::TextBox, conv::wrap, text::Line},
 };
 
-use crate::util::conv::wrap;
+use self::log::MessageLog;
 
 /// Different locations for text to be rendered
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
 pub enum TextLocation {
     Center,
+    Top,
     Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Where a location is anchored along one axis
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+impl TextLocation {
+    /// The maximum amount of messages shown at this location at the same time
+    fn limit(&self) -> usize {
+        match self {
+            TextLocation::Center => 3,
+            TextLocation::Top | TextLocation::Bottom => 2,
+            _ => 3,
+        }
+    }
+
+    /// The horizontal and vertical anchors of this location
+    fn anchors(&self) -> (Anchor, Anchor) {
+        match self {
+            TextLocation::Center => (Anchor::Middle, Anchor::Middle),
+            TextLocation::Top => (Anchor::Middle, Anchor::Start),
+            TextLocation::Bottom => (Anchor::Middle, Anchor::End),
+            TextLocation::Left => (Anchor::Start, Anchor::Middle),
+            TextLocation::Right => (Anchor::End, Anchor::Middle),
+            TextLocation::TopLeft => (Anchor::Start, Anchor::Start),
+            TextLocation::TopRight => (Anchor::End, Anchor::Start),
+            TextLocation::BottomLeft => (Anchor::Start, Anchor::End),
+            TextLocation::BottomRight => (Anchor::End, Anchor::End),
+        }
+    }
+}
+
+/// Categories of messages, used to filter the message log
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum MessageCategory {
+    Tutorial,
+    Shop,
+    Fight,
+    Inventory,
+    Counter,
+    Prestige,
+}
+
+impl MessageCategory {
+    /// The translated name of this category
+    pub fn name(&self) -> &'static str {
+        match self {
+            MessageCategory::Tutorial => t("log.category.tutorial"),
+            MessageCategory::Shop => t("log.category.shop"),
+            MessageCategory::Fight => t("log.category.fight"),
+            MessageCategory::Inventory => t("log.category.inventory"),
+            MessageCategory::Counter => t("log.category.counter"),
+            MessageCategory::Prestige => t("log.category.prestige"),
+        }
+    }
+}
+
+/// Priority of a message.
+/// Info messages stack on top of each other, critical messages interrupt all info messages at their location.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug)]
+pub enum Priority {
+    Info,
+    Critical,
 }
 
 /// A message to be displayed
 pub struct Message {
-    pub text: StyledContent<String>,
+    pub text: Line,
     pub location: TextLocation,
     pub duration: f32,
+    pub category: MessageCategory,
+    pub priority: Priority,
+}
+
+/// The callback of a prompt, called with the chosen answer
+pub type PromptCallback = Box<dyn FnOnce(char, &mut State, &mut MessageManager)>;
+
+/// A message that asks the player to make a choice.
+/// It blocks all other input until one of its choices is pressed.
+pub struct Prompt {
+    pub text: Line,
+    pub choices: Vec<char>,
+    pub category: MessageCategory,
+    pub callback: PromptCallback,
+}
+
+/// A message that is queued or being displayed, with the amount of times it was sent
+struct ActiveMessage {
+    message: Message,
+    count: u32,
+    total: f32,
 }
 
 /// A manager for messages
-/// It keeps track of messages and their timers, and renders them
+/// It keeps track of messages and their timers, and renders them.
+/// Every message is also kept in the log, so it can be read again later.
+#[derive(Default)]
 pub struct MessageManager {
-    messages: VecDeque<Message>,
+    messages: VecDeque<ActiveMessage>,
+    prompts: VecDeque<Prompt>,
+    pub log: MessageLog,
+    time: f32,
 }
 
-impl Default for MessageManager {
-    fn default() -> Self {
-        Self {
-            messages: VecDeque::new(),
+impl MessageManager {
+    /// Add a message to the queue.
+    /// If the same message is already queued at the same location, it is collapsed into that one instead.
+    pub fn add_message(&mut self, message: Message) {
+        self.log.add(&message.text, message.category, self.time);
+
+        if let Some(existing) = self
+            .messages
+            .iter_mut()
+            .find(|m| m.message.location == message.location && m.message.text == message.text)
+        {
+            existing.count += 1;
+            existing.message.duration = existing.message.duration.max(message.duration);
+            existing.total = existing.total.max(message.duration);
+            existing.message.priority = existing.message.priority.max(message.priority);
+            return;
+        }
+
+        let message = ActiveMessage {
+            total: message.duration,
+            message,
+            count: 1,
+        };
+        if message.message.priority == Priority::Critical {
+            self.messages.push_front(message);
+        } else {
+            self.messages.push_back(message);
         }
     }
-}
 
-impl MessageManager {
-    /// Add a message to the queue
-    pub fn add_message(&mut self, message: Message) {
-        self.messages.push_back(message);
+    /// Add a prompt to the queue, it is shown once all earlier prompts are answered
+    pub fn add_prompt(&mut self, prompt: Prompt) {
+        self.log.add(&prompt.text, prompt.category, self.time);
+        self.prompts.push_back(prompt);
+    }
+
+    /// Check if there is a prompt waiting for an answer
+    pub fn has_prompt(&self) -> bool {
+        !self.prompts.is_empty()
     }
 
-    /// Update the timers on all messages, removing any that have expired
+    /// Answer the current prompt if the key is one of its choices, invoking its callback
+    pub fn answer(&mut self, key: KeyCode, state: &mut State) {
+        if let KeyCode::Char(c) = key {
+            if self.prompts.front().is_some_and(|p| p.choices.contains(&c)) {
+                let prompt = self.prompts.pop_front().expect("A prompt was just found");
+                (prompt.callback)(c, state, self);
+            }
+        }
+    }
+
+    /// Update the timers on all visible messages, removing any that have expired
     pub fn update(&mut self, key: KeyCode, delta: f32) {
-        if let Some(message) = self.messages.front_mut() {
-            message.duration -= delta;
-            if message.duration <= 0.0 || key == KeyCode::Enter {
-                self.messages.pop_front();
+        self.time += delta;
+
+        for location in all::<TextLocation>() {
+            for i in self.visible(location) {
+                self.messages[i].message.duration -= delta;
+                if key == KeyCode::Enter {
+                    self.messages[i].message.duration = 0.0;
+                }
             }
         }
+
+        self.messages.retain(|m| m.message.duration > 0.0);
     }
 
-    /// Render the first message in the queue
-    pub fn render_one(&mut self) {
-        if let Some(message) = self.messages.front() {
-            let (t_c, t_r) = crossterm::terminal::size().unwrap_or((80, 24));
-            let lines = wrap(message.text.clone(), t_c / 2);
-
-            lines.iter().enumerate().for_each(|(i, (len, line))| {
-                queue!(
-                    stdout(),
-                    match message.location {
-                        TextLocation::Center => {
-                            let col = t_c / 2 - *len / 2;
-                            let row = t_r / 2 + i as u16;
-
-                            MoveTo(col, row)
-                        }
-                        TextLocation::Bottom => {
-                            let col = t_c / 2 - *len / 2;
-                            let row = t_r - (lines.len() - i) as u16;
-                            MoveTo(col, row)
-                        }
-                    },
-                    Print(line)
-                )
-                .expect("Failed to queue message render")
-            });
+    /// Render all visible messages in boxes, stacked per location
+    pub fn render(&mut self) {
+        let (t_c, t_r) = crossterm::terminal::size().unwrap_or((80, 24));
+
+        for location in all::<TextLocation>() {
+            let (horizontal, vertical) = location.anchors();
+            let max_width = if horizontal == Anchor::Middle {
+                t_c / 2
+            } else {
+                t_c / 3
+            };
+
+            let boxes: Vec<TextBox<Line>> = self
+                .visible(location)
+                .into_iter()
+                .map(|i| {
+                    let m = &self.messages[i];
+                    let mut text = m.message.text.clone();
+                    if m.count > 1 {
+                        text.push(ContentStyle::new(), &format!(" ×{}", m.count));
+                    }
+
+                    let lines = wrap(&text, max_width.saturating_sub(4));
+                    let width = lines.iter().map(|(len, _)| *len).max().unwrap_or(0) + 4;
+                    TextBox {
+                        col: match horizontal {
+                            Anchor::Start => 1,
+                            Anchor::Middle => (t_c / 2).saturating_sub(width / 2),
+                            Anchor::End => t_c.saturating_sub(width + 1),
+                        },
+                        row: 0,
+                        width,
+                        lines,
+                        progress: Some(m.message.duration / m.total),
+                    }
+                })
+                .collect();
+
+            let height: u16 = boxes.iter().map(|b| b.height()).sum();
+            let mut row = match vertical {
+                // leave the title and divider visible
+                Anchor::Start => 2,
+                Anchor::Middle => (t_r / 2).saturating_sub(height / 2),
+                Anchor::End => t_r.saturating_sub(height),
+            };
+
+            for mut text_box in boxes {
+                text_box.row = row;
+                row += text_box.height();
+                queue!(stdout(), text_box).expect("Failed to queue message render");
+            }
         }
+
+        // the current prompt is drawn on top of everything
+        if let Some(prompt) = self.prompts.front() {
+            let choices = prompt
+                .choices
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            let mut text = prompt.text.clone();
+            text.push(ContentStyle::new(), &format!(" [{}]", choices));
+
+            let lines = wrap(&text, (t_c / 2).saturating_sub(4));
+            let width = lines.iter().map(|(len, _)| *len).max().unwrap_or(0) + 4;
+            let text_box = TextBox {
+                col: (t_c / 2).saturating_sub(width / 2),
+                row: (t_r / 2).saturating_sub(lines.len() as u16 / 2 + 1),
+                width,
+                lines,
+                progress: None,
+            };
+            queue!(stdout(), text_box).expect("Failed to queue prompt render");
+        }
+    }
+
+    /// Get the indices of the messages currently shown at a location.
+    /// Critical messages hide all info messages, and at most `limit` messages are shown.
+    fn visible(&self, location: TextLocation) -> Vec<usize> {
+        let critical = self
+            .messages
+            .iter()
+            .any(|m| m.message.location == location && m.message.priority == Priority::Critical);
+
+        self.messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| {
+                m.message.location == location
+                    && (!critical || m.message.priority == Priority::Critical)
+            })
+            .map(|(i, _)| i)
+            .take(location.limit())
+            .collect()
     }
 }
+
+pub mod log;
@@ -0,0 +1,109 @@
+use crossterm::event::KeyCode;
+use enum_iterator::{all, Sequence};
+
+use super::MessageCategory;
+use crate::{
+    locale::{t, tf},
+    util::{markup::parse, text::Line},
+};
+
+/// An entry in the message log
+pub struct LogEntry {
+    pub text: Line,
+    pub category: MessageCategory,
+    pub time: f32,
+}
+
+/// The message log
+/// It retains every message that has been shown, and can be opened from any screen to read them again.
+#[derive(Default)]
+pub struct MessageLog {
+    entries: Vec<LogEntry>,
+    pub open: bool,
+    scroll: usize,
+    filter: Option<MessageCategory>,
+}
+
+impl MessageLog {
+    /// Add a message to the log
+    pub fn add(&mut self, text: &Line, category: MessageCategory, time: f32) {
+        self.entries.push(LogEntry {
+            text: text.clone(),
+            category,
+            time,
+        });
+    }
+
+    /// Process input while the log is open
+    pub fn process_input(&mut self, key: KeyCode, height: usize) {
+        let max_scroll = self.filtered().count().saturating_sub(height);
+
+        match key {
+            KeyCode::Tab | KeyCode::Char('q') => self.open = false,
+            KeyCode::Up => self.scroll = (self.scroll + 1).min(max_scroll),
+            KeyCode::Down => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageUp => self.scroll = (self.scroll + height).min(max_scroll),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(height),
+            KeyCode::Left => self.set_filter(
+                self.filter
+                    .map_or_else(|| all::<MessageCategory>().last(), |f| f.previous()),
+            ),
+            KeyCode::Right => self.set_filter(
+                self.filter
+                    .map_or_else(|| all::<MessageCategory>().next(), |f| f.next()),
+            ),
+            _ => {}
+        }
+    }
+
+    /// Get the text of the latest messages, oldest first
+    pub fn latest(&self, count: usize) -> Vec<Line> {
+        let start = self.entries.len().saturating_sub(count);
+        self.entries[start..]
+            .iter()
+            .map(|e| e.text.clone())
+            .collect()
+    }
+
+    /// Get the top bar of the log
+    pub fn get_top_bar(&self) -> Line {
+        parse(&tf(
+            "log.top_bar",
+            &[("filter", &self.filter.map_or(t("log.all"), |f| f.name()))],
+        ))
+    }
+
+    /// Render the visible part of the log, the newest message at the bottom
+    pub fn render(&self, height: usize) -> Vec<Line> {
+        let entries: Vec<&LogEntry> = self.filtered().collect();
+        let end = entries.len() - self.scroll.min(entries.len());
+        let start = end.saturating_sub(height);
+
+        entries[start..end]
+            .iter()
+            .map(|entry| {
+                let mut line = parse(&format!(
+                    "{{dim}}[{:02}:{:02}] [{}]{{/}} ",
+                    entry.time as u32 / 60,
+                    entry.time as u32 % 60,
+                    entry.category.name()
+                ));
+                line.append(entry.text.clone());
+                line
+            })
+            .collect()
+    }
+
+    /// Set the category filter, and reset the scroll position
+    fn set_filter(&mut self, filter: Option<MessageCategory>) {
+        self.filter = filter;
+        self.scroll = 0;
+    }
+
+    /// Get all entries that match the current filter
+    fn filtered(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries
+            .iter()
+            .filter(move |e| self.filter.is_none_or(|f| e.category == f))
+    }
+}
@@ -0,0 +1,50 @@
+use std::{fs, io};
+
+use enum_iterator::all;
+
+use crate::{
+    state::State,
+    util::{
+        config::pairs,
+        rng::{Random, Rng, Stream},
+    },
+};
+
+/// The file the game is saved to, in the working directory like the settings file
+const SAVE_FILE: &str = "save.cfg";
+
+/// Write the parts of the state that are kept between sessions to the save file.
+/// For now this is the random state: the seed and the position of every stream,
+/// so the next session continues with new numbers instead of replaying the same ones.
+pub fn save(state: &State) -> io::Result<()> {
+    let mut text = String::from("# Saved game, written when the game quits\n");
+    text += &format!("rng.seed = {}\n", state.rng.seed());
+    for stream in all::<Stream>() {
+        text += &format!("rng.{} = {}\n", stream.name(), state.rng.state(stream));
+    }
+    fs::write(SAVE_FILE, text)
+}
+
+/// Restore the state from the save file, if there is one
+pub fn load(state: &mut State) {
+    let Ok(text) = fs::read_to_string(SAVE_FILE) else {
+        return;
+    };
+
+    let pairs: Vec<(&str, &str)> = pairs(&text).collect();
+    let value = |key: &str| {
+        pairs
+            .iter()
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v.parse::<u64>().ok())
+    };
+
+    if let Some(seed) = value("rng.seed") {
+        state.rng = Random::new(seed);
+        for stream in all::<Stream>() {
+            if let Some(rng) = value(&format!("rng.{}", stream.name())) {
+                *state.rng.stream(stream) = Rng::from_state(rng);
+            }
+        }
+    }
+}
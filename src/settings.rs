@@ -0,0 +1,66 @@
+use std::fs;
+
+use crate::util::config::pairs;
+
+/// The file settings are read from
+const SETTINGS_FILE: &str = "settings.cfg";
+
+/// Settings struct:
+/// The settings of the application, read from the settings file and overridden by command line arguments.
+/// Arguments are given as `--key value` or `--key=value`, using the same keys as the settings file.
+pub struct Settings {
+    pub lang: String,
+    pub theme: String,
+    pub number_format: String,
+    /// The seed for the random numbers, replacing the saved one
+    pub seed: Option<u64>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            lang: "en".to_string(),
+            theme: "default".to_string(),
+            number_format: "suffix".to_string(),
+            seed: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Load the settings from the settings file and the command line
+    pub fn load() -> Self {
+        let mut settings = Settings::default();
+
+        if let Ok(text) = fs::read_to_string(SETTINGS_FILE) {
+            pairs(&text).for_each(|(key, value)| settings.set(key, value));
+        }
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if let Some(arg) = arg.strip_prefix("--") {
+                match arg.split_once('=') {
+                    Some((key, value)) => settings.set(key, value),
+                    None => {
+                        if let Some(value) = args.next() {
+                            settings.set(arg, &value)
+                        }
+                    }
+                }
+            }
+        }
+
+        settings
+    }
+
+    /// Set a setting by its key, ignoring unknown keys
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "lang" => self.lang = value.to_string(),
+            "theme" => self.theme = value.to_string(),
+            "number_format" => self.number_format = value.to_string(),
+            "seed" => self.seed = value.parse().ok(),
+            _ => {}
+        }
+    }
+}
@@ -1,4 +1,13 @@
-use crate::feature::{fight::FightData, inventory::Inventory, shop::Upgrades};
+use crate::{
+    feature::{
+        counter::{Generators, Milestones},
+        fight::FightData,
+        inventory::Inventory,
+        prestige::Prestige,
+        shop::Upgrades,
+    },
+    util::{number::BigNum, rng::Random},
+};
 use crossterm::event::KeyCode;
 
 /// State struct:
@@ -7,9 +16,14 @@ pub struct State {
     pub key: KeyCode,
     pub selected_feature: Option<usize>,
     pub quit: bool,
+    /// The random numbers of every system, started from the saved seed
+    pub rng: Random,
 
-    pub count: i32,
+    pub count: BigNum,
+    pub generators: Generators,
+    pub milestones: Milestones,
     pub fight: FightData,
     pub inventory: Inventory,
     pub upgrades: Upgrades,
+    pub prestige: Prestige,
 }
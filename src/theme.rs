@@ -0,0 +1,138 @@
+use std::sync::OnceLock;
+
+use crossterm::style::{Attribute, Color, ContentStyle, Stylize};
+use enum_iterator::{all, Sequence};
+
+use crate::feature::inventory::Rarity;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Semantic roles that text can have, each theme decides how they look
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Role {
+    Title,
+    Gold,
+    Danger,
+    Success,
+    Info,
+    Dim,
+    Rarity(Rarity),
+}
+
+/// The built-in color themes
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum Theme {
+    Default,
+    HighContrast,
+    /// A palette that stays distinguishable with color blindness, and marks rarities with symbols
+    Colorblind,
+    /// No colors at all, used when `NO_COLOR` is set
+    Mono,
+}
+
+/// Select the theme by its name, like `high-contrast`.
+/// If the `NO_COLOR` environment variable is set, the monochrome theme is always used.
+pub fn init(name: &str) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let theme = if no_color {
+        Theme::Mono
+    } else {
+        all::<Theme>()
+            .find(|t| t.name() == name)
+            .unwrap_or(Theme::Default)
+    };
+    let _ = THEME.set(theme);
+}
+
+/// Get the selected theme
+pub fn theme() -> Theme {
+    *THEME.get_or_init(|| Theme::Default)
+}
+
+impl Theme {
+    /// The name of this theme, as used in the settings
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Default => "default",
+            Theme::HighContrast => "high-contrast",
+            Theme::Colorblind => "colorblind",
+            Theme::Mono => "mono",
+        }
+    }
+
+    /// Check if this theme uses colors
+    pub fn has_colors(&self) -> bool {
+        *self != Theme::Mono
+    }
+
+    /// The style of a role in this theme
+    pub fn style(&self, role: Role) -> ContentStyle {
+        let style = ContentStyle::new();
+        match self {
+            Theme::Default => match role {
+                Role::Title => style.white().bold(),
+                Role::Gold => style.dark_yellow().bold(),
+                Role::Danger => style.red(),
+                Role::Success => style.green(),
+                Role::Info => style.cyan(),
+                Role::Dim => style.dark_grey(),
+                Role::Rarity(Rarity::Common) => style.white(),
+                Role::Rarity(Rarity::Uncommon) => style.green(),
+                Role::Rarity(Rarity::Rare) => style.blue(),
+                Role::Rarity(Rarity::Epic) => style.magenta(),
+                Role::Rarity(Rarity::Legendary) => style.yellow(),
+                Role::Rarity(Rarity::Mythic) => style.red().bold(),
+            },
+            Theme::HighContrast => match role {
+                Role::Title => style.white().bold().underlined(),
+                Role::Gold => style.yellow().bold(),
+                Role::Danger => style.red().bold(),
+                Role::Success => style.green().bold(),
+                Role::Info => style.cyan().bold(),
+                Role::Dim => style.grey(),
+                Role::Rarity(Rarity::Common) => style.white().bold(),
+                Role::Rarity(Rarity::Uncommon) => style.green().bold(),
+                Role::Rarity(Rarity::Rare) => style.blue().bold(),
+                Role::Rarity(Rarity::Epic) => style.magenta().bold(),
+                Role::Rarity(Rarity::Legendary) => style.yellow().bold(),
+                Role::Rarity(Rarity::Mythic) => style.red().bold().underlined(),
+            },
+            // based on the Okabe-Ito palette
+            Theme::Colorblind => match role {
+                Role::Title => style.white().bold(),
+                Role::Gold => style.with(Color::AnsiValue(220)).bold(),
+                Role::Danger => style.with(Color::AnsiValue(202)),
+                Role::Success => style.with(Color::AnsiValue(36)),
+                Role::Info => style.with(Color::AnsiValue(117)),
+                Role::Dim => style.with(Color::AnsiValue(244)),
+                Role::Rarity(Rarity::Common) => style.with(Color::AnsiValue(250)),
+                Role::Rarity(Rarity::Uncommon) => style.with(Color::AnsiValue(117)),
+                Role::Rarity(Rarity::Rare) => style.with(Color::AnsiValue(33)),
+                Role::Rarity(Rarity::Epic) => style.with(Color::AnsiValue(175)),
+                Role::Rarity(Rarity::Legendary) => style.with(Color::AnsiValue(214)),
+                Role::Rarity(Rarity::Mythic) => style.with(Color::AnsiValue(202)).bold(),
+            },
+            Theme::Mono => match role {
+                Role::Title | Role::Gold | Role::Danger => style.bold(),
+                Role::Dim => style.attribute(Attribute::Dim),
+                Role::Rarity(Rarity::Mythic) => style.bold(),
+                _ => style,
+            },
+        }
+    }
+
+    /// The symbol shown in front of an item of a rarity, for themes that do not rely on color alone
+    pub fn symbol(&self, rarity: Rarity) -> Option<&'static str> {
+        match self {
+            Theme::Colorblind | Theme::Mono => Some(match rarity {
+                Rarity::Common => "○ ",
+                Rarity::Uncommon => "● ",
+                Rarity::Rare => "◆ ",
+                Rarity::Epic => "★ ",
+                Rarity::Legendary => "♛ ",
+                Rarity::Mythic => "✦ ",
+            }),
+            _ => None,
+        }
+    }
+}
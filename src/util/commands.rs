@@ -3,11 +3,15 @@ use std::iter;
 
 use crossterm::{
     csi,
-    cursor::MoveToNextLine,
+    cursor::{MoveTo, MoveToNextLine},
+    style::ContentStyle,
     terminal::{Clear, ClearType},
     Command,
 };
 
+use super::{markup::parse, text::Line};
+use crate::theme::{theme, Role};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PrintAll<T: std::fmt::Display>(pub Vec<T>);
 
@@ -60,7 +64,7 @@ impl Command for Divider {
             write!(
                 f,
                 "{}",
-                iter::repeat(self.0).take(size as usize).collect::<String>()
+                iter::repeat_n(self.0, size as usize).collect::<String>()
             )
         } else {
             panic!("Failed to get terminal size");
@@ -77,3 +81,127 @@ impl Command for Divider {
         true
     }
 }
+
+/// A bordered box with padding around its lines, drawn at a position.
+/// The lines are given with their display width, so they can be padded to fill the box and clear what is below.
+/// If a progress is given, the bottom border shows how much of it remains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBox<T: std::fmt::Display> {
+    pub col: u16,
+    pub row: u16,
+    pub width: u16,
+    pub lines: Vec<(u16, T)>,
+    pub progress: Option<f32>,
+}
+
+impl<T: std::fmt::Display> TextBox<T> {
+    /// The total height of the box, including its borders
+    pub fn height(&self) -> u16 {
+        self.lines.len() as u16 + 2
+    }
+}
+
+impl<T: std::fmt::Display> Command for TextBox<T> {
+    fn write_ansi(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        let inner = self.width.saturating_sub(2) as usize;
+
+        MoveTo(self.col, self.row).write_ansi(f)?;
+        write!(f, "┌{}┐", "─".repeat(inner))?;
+
+        for (i, (len, line)) in self.lines.iter().enumerate() {
+            MoveTo(self.col, self.row + 1 + i as u16).write_ansi(f)?;
+            write!(
+                f,
+                "│ {}{} │",
+                line,
+                " ".repeat(inner.saturating_sub(*len as usize + 2))
+            )?;
+        }
+
+        MoveTo(self.col, self.row + 1 + self.lines.len() as u16).write_ansi(f)?;
+        let filled = self
+            .progress
+            .map_or(0, |p| (p.clamp(0.0, 1.0) * inner as f32).ceil() as usize);
+        write!(f, "└{}{}┘", "━".repeat(filled), "─".repeat(inner - filled))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> Result<(), std::io::Error> {
+        panic!("tried to execute Print command using WinAPI, use ANSI instead");
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
+
+/// A horizontal bar showing how full something is, like health or a timer, with a label in front and text behind it.
+/// The bar is styled by the first threshold that its ratio is at or below.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gauge {
+    pub label: String,
+    pub label_width: usize,
+    pub ratio: f64,
+    pub width: u16,
+    pub thresholds: Vec<(f64, Role)>,
+    pub text: String,
+}
+
+impl Gauge {
+    /// Thresholds for health: dangerous when low, fine when high
+    pub fn health() -> Vec<(f64, Role)> {
+        vec![
+            (0.25, Role::Danger),
+            (0.5, Role::Gold),
+            (1.0, Role::Success),
+        ]
+    }
+
+    /// Thresholds for a timer that is ready when full
+    pub fn readiness() -> Vec<(f64, Role)> {
+        vec![(0.999, Role::Info), (1.0, Role::Success)]
+    }
+
+    /// Get the gauge as a styled line
+    pub fn line(&self) -> Line {
+        let ratio = if self.ratio.is_finite() {
+            self.ratio.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let filled = (ratio * self.width as f64).round() as usize;
+        let role = self
+            .thresholds
+            .iter()
+            .find(|(max, _)| ratio <= *max)
+            .map_or(Role::Info, |(_, role)| *role);
+
+        let mut line = parse(&self.label);
+        let padding = self.label_width.saturating_sub(line.width()) + 1;
+        line.push(ContentStyle::new(), &" ".repeat(padding));
+        line.push(theme().style(role), &"█".repeat(filled));
+        line.push(
+            theme().style(Role::Dim),
+            &"░".repeat(self.width as usize - filled),
+        );
+        line.append(parse(&format!(" {}", self.text)));
+        line
+    }
+}
+
+impl Command for Gauge {
+    fn write_ansi(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        write!(f, "{}", self.line())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> Result<(), std::io::Error> {
+        panic!("tried to execute Print command using WinAPI, use ANSI instead");
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,89 @@
+use std::{fs, str::FromStr};
+
+/// Read `key = value` pairs from a text file.
+/// Empty lines and lines starting with `#` are skipped.
+/// A value can be wrapped in quotes to keep whitespace at its start or end.
+pub fn pairs(text: &str) -> impl Iterator<Item = (&str, &str)> {
+    lines(text).filter_map(pair)
+}
+
+/// A section of a text file: its name, the line number it starts on, and its pairs
+type Section<'a> = (&'a str, usize, Vec<(&'a str, &'a str)>);
+
+/// Read sections of `key = value` pairs from a text file.
+/// A section starts with its name in brackets, like `[slime]`, and pairs before the first section are skipped.
+pub fn sections(text: &str) -> Vec<Section<'_>> {
+    let mut sections: Vec<Section> = Vec::new();
+    for (number, line) in numbered_lines(text) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.trim(), number, Vec::new()));
+        } else if let (Some((_, _, pairs)), Some(pair)) = (sections.last_mut(), pair(line)) {
+            pairs.push(pair);
+        }
+    }
+    sections
+}
+
+/// Load a data file with a value for every section, parsed from the section name and its pairs.
+/// A file at this path in the working directory takes precedence over the built-in text.
+/// Section names have to be unique.
+/// Gives every problem in the file, with the line and name of the section it is in, if there are any.
+pub fn load_sections<T>(
+    path: &str,
+    built_in: &str,
+    parse: impl Fn(&str, &[(&str, &str)]) -> Result<T, Vec<String>>,
+) -> Result<Vec<T>, Vec<String>> {
+    let text = fs::read_to_string(path).unwrap_or_else(|_| built_in.to_string());
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    let sections = sections(&text);
+    for (i, (name, line, pairs)) in sections.iter().enumerate() {
+        let error = |e: &str| format!("{}:{}: [{}] {}", path, line, name, e);
+        if sections[..i].iter().any(|(other, _, _)| other == name) {
+            errors.push(error("is already defined above"));
+        }
+        match parse(name, pairs) {
+            Ok(value) => values.push(value),
+            Err(e) => errors.extend(e.iter().map(|e| error(e))),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parse the value of a key, describing the problem if it is not valid
+pub fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("`{}` is not a valid value for `{}`", value, key))
+}
+
+/// All lines with content, trimmed
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    numbered_lines(text).map(|(_, line)| line)
+}
+
+/// All lines with content, trimmed, with their line numbers starting at 1
+fn numbered_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .map(str::trim)
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Read a single `key = value` pair
+fn pair(line: &str) -> Option<(&str, &str)> {
+    line.split_once('=').map(|(key, value)| {
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        (key.trim(), value)
+    })
+}
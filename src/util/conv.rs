@@ -1,4 +1,6 @@
-use crossterm::{event::KeyCode, style::StyledContent};
+use crossterm::event::KeyCode;
+
+use super::text::{Line, Wrapper};
 
 /// Get a string representation of a key
 pub fn get_string(key: KeyCode) -> String {
@@ -12,39 +14,19 @@ pub fn get_string(key: KeyCode) -> String {
     }
 }
 
-/// Wraps a string to a certain width, returning a vector of lines and their lengths
-pub fn wrap(text: StyledContent<String>, width: u16) -> Vec<(u16, String)> {
-    let mut lines = Vec::new();
-    let mut line: String = String::new();
-    let mut len: u16 = 0;
-
-    for word in text.to_string().split_whitespace() {
-        if len + word.len() as u16 > width {
-            lines.push((len, line));
-            line = String::new();
-            len = 0;
-        }
-
-        line.push_str(word);
-        line.push(' ');
-        len += word.len() as u16 + 1;
-    }
-
-    lines.push((len, line));
-
-    lines
+/// Wraps a line to a certain width, returning a vector of lines and their display widths.
+/// Styles are kept on every line, and words longer than the width are broken up.
+pub fn wrap(line: &Line, width: u16) -> Vec<(u16, Line)> {
+    let mut wrapper = Wrapper::new(width);
+    line.0.iter().for_each(|span| wrapper.add(span));
+    wrapper.finish()
 }
 
-/// Get the number from a key
-pub fn to_number(c: KeyCode) -> Option<usize> {
-    match c {
-        KeyCode::Char(c) => {
-            if let Some(d) = c.to_digit(10) {
-                Some(d as usize)
-            } else {
-                None
-            }
-        }
-        _ => None,
-    }
+/// Wraps all lines to a certain width
+pub fn wrap_all<T: Into<Line>>(lines: Vec<T>, width: u16) -> Vec<Line> {
+    lines
+        .into_iter()
+        .flat_map(|line| wrap(&line.into(), width))
+        .map(|(_, line)| line)
+        .collect()
 }
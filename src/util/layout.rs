@@ -0,0 +1,155 @@
+use crossterm::style::ContentStyle;
+
+use super::text::Line;
+
+/// The size of a part of a layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Size {
+    /// A fixed amount of columns or rows
+    Fixed(u16),
+    /// A share of the space left after all fixed parts
+    Ratio(u16),
+}
+
+/// The direction the parts of a split are placed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Parts are placed next to each other, as columns
+    Horizontal,
+    /// Parts are placed below each other, as rows
+    Vertical,
+}
+
+/// A panel with a title and lines of content, optionally surrounded by a border
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panel {
+    pub title: Line,
+    pub lines: Vec<Line>,
+    pub border: bool,
+}
+
+/// A layout: either a single panel, or a split of the space into more layouts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Layout {
+    Panel(Panel),
+    Split(Direction, Vec<(Size, Layout)>),
+}
+
+impl Layout {
+    /// Render the layout into exactly `height` lines of exactly `width` columns
+    pub fn render(&self, width: u16, height: u16) -> Vec<Line> {
+        match self {
+            Layout::Panel(panel) => panel.render(width, height),
+            Layout::Split(Direction::Vertical, parts) => {
+                let sizes = split(parts.iter().map(|(s, _)| *s), height);
+                parts
+                    .iter()
+                    .zip(sizes)
+                    .flat_map(|((_, layout), h)| layout.render(width, h))
+                    .collect()
+            }
+            Layout::Split(Direction::Horizontal, parts) => {
+                let sizes = split(parts.iter().map(|(s, _)| *s), width);
+                let columns: Vec<Vec<Line>> = parts
+                    .iter()
+                    .zip(sizes)
+                    .map(|((_, layout), w)| layout.render(w, height))
+                    .collect();
+
+                (0..height as usize)
+                    .map(|row| {
+                        let mut line = Line::default();
+                        columns
+                            .iter()
+                            .for_each(|column| line.append(column[row].clone()));
+                        line
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl Panel {
+    /// Render the panel into exactly `height` lines of exactly `width` columns
+    fn render(&self, width: u16, height: u16) -> Vec<Line> {
+        let (width, height) = (width as usize, height as usize);
+        if !self.border || width < 2 || height < 2 {
+            return (0..height)
+                .map(|i| self.lines.get(i).cloned().unwrap_or_default().fit(width))
+                .collect();
+        }
+
+        let inner = width - 2;
+        let style = ContentStyle::new();
+
+        let mut top = Line::default();
+        top.push(style, "┌");
+        let mut title = Line::default();
+        title.push(style, " ");
+        title.append(self.title.clone());
+        title.push(style, " ");
+        let title = if title.width() > inner {
+            title.fit(inner)
+        } else {
+            title
+        };
+        let rest = inner - title.width();
+        top.append(title);
+        top.push(style, &"─".repeat(rest));
+        top.push(style, "┐");
+
+        let mut lines = vec![top];
+        for i in 0..height - 2 {
+            let mut line = Line::default();
+            line.push(style, "│");
+            line.append(self.lines.get(i).cloned().unwrap_or_default().fit(inner));
+            line.push(style, "│");
+            lines.push(line);
+        }
+
+        let mut bottom = Line::default();
+        bottom.push(style, &format!("└{}┘", "─".repeat(inner)));
+        lines.push(bottom);
+        lines
+    }
+}
+
+/// Get the size of the screen below the top bar and divider, where features are rendered
+pub fn content_size() -> (u16, u16) {
+    let (t_c, t_r) = crossterm::terminal::size().unwrap_or((80, 24));
+    (t_c, t_r.saturating_sub(2))
+}
+
+/// Divide a total amount of space over sizes.
+/// Fixed sizes are given first, and the rest is shared by ratio, with rounding leftovers going to the last part.
+fn split(sizes: impl Iterator<Item = Size> + Clone, total: u16) -> Vec<u16> {
+    let fixed: u16 = sizes
+        .clone()
+        .map(|s| if let Size::Fixed(n) = s { n } else { 0 })
+        .sum();
+    let ratios: u16 = sizes
+        .clone()
+        .map(|s| if let Size::Ratio(n) = s { n } else { 0 })
+        .sum();
+    let left = total.saturating_sub(fixed);
+
+    let mut result: Vec<u16> = sizes
+        .map(|s| match s {
+            Size::Fixed(n) => n,
+            Size::Ratio(n) => (left as u32 * n as u32 / ratios.max(1) as u32) as u16,
+        })
+        .collect();
+
+    // give space lost to rounding to the last part, and cut off parts that do not fit
+    let used: u16 = result.iter().sum();
+    if let Some(last) = result.last_mut() {
+        *last += total.saturating_sub(used);
+    }
+    let mut left = total;
+    for size in result.iter_mut() {
+        *size = (*size).min(left);
+        left -= *size;
+    }
+    result
+}
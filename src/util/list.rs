@@ -0,0 +1,90 @@
+use crossterm::{
+    event::KeyCode,
+    style::{ContentStyle, StyledContent, Stylize},
+};
+
+use super::{conv::wrap, text::Line};
+
+/// A list of items with a cursor.
+/// The list scrolls to keep the cursor in view, and items longer than the width are wrapped.
+#[derive(Debug, Default)]
+pub struct List {
+    cursor: usize,
+    scroll: usize,
+}
+
+impl List {
+    /// Move the cursor with the arrow keys, page up/down and home/end.
+    /// Returns the index of the selected item when Enter is pressed.
+    pub fn process_input(&mut self, key: KeyCode, len: usize, height: usize) -> Option<usize> {
+        let page = height.max(1);
+        let last = len.saturating_sub(1);
+        self.cursor = match key {
+            KeyCode::Up => self.cursor.saturating_sub(1),
+            KeyCode::Down => self.cursor + 1,
+            KeyCode::PageUp => self.cursor.saturating_sub(page),
+            KeyCode::PageDown => self.cursor + page,
+            KeyCode::Home => 0,
+            KeyCode::End => last,
+            _ => self.cursor,
+        }
+        .min(last);
+
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + page {
+            self.scroll = self.cursor + 1 - page;
+        }
+
+        (key == KeyCode::Enter && len > 0).then_some(self.cursor)
+    }
+
+    /// The index of the item under the cursor
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Render the items that fit in `height` rows of `width` columns, with the cursor highlighted
+    pub fn render(&self, items: Vec<Line>, width: u16, height: usize) -> Vec<Line> {
+        let cursor = self.cursor.min(items.len().saturating_sub(1));
+        let items: Vec<Vec<Line>> = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                wrap(item, width.saturating_sub(2))
+                    .into_iter()
+                    .enumerate()
+                    .map(|(row, (_, text))| {
+                        let marker = if i == cursor && row == 0 { "> " } else { "  " };
+                        let mut line = Line::default();
+                        line.push(ContentStyle::new(), marker);
+                        if i == cursor {
+                            text.0.iter().for_each(|span| {
+                                line.0.push(StyledContent::new(
+                                    span.style().reverse(),
+                                    span.content().clone(),
+                                ))
+                            });
+                        } else {
+                            line.append(text);
+                        }
+                        line
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // start at the scroll position, but move down if the wrapped items push the cursor out of view
+        let mut start = self.scroll.min(cursor);
+        while start < cursor && items[start..=cursor].iter().map(Vec::len).sum::<usize>() > height {
+            start += 1;
+        }
+
+        items
+            .into_iter()
+            .skip(start)
+            .flatten()
+            .take(height)
+            .collect()
+    }
+}
@@ -0,0 +1,135 @@
+use crossterm::style::ContentStyle;
+
+use super::{
+    style::{tag_prefix, tag_style},
+    text::Line,
+};
+
+/// Parse markup into a styled line.
+/// A tag like `{gold}` or `{rarity:rare}` styles all text up to its closing `{/}`, and tags can be nested.
+/// Unknown tags are kept as text, and a literal brace is written as `{{` or `}}`.
+///
+/// Example: `"You bought {rarity:rare}Damage{/} for {gold}5{/} gold"`
+pub fn parse(text: &str) -> Line {
+    let mut line = Line::default();
+    let mut styles = vec![ContentStyle::new()];
+    let mut rest = text;
+
+    while let Some(i) = rest.find(['{', '}']) {
+        let style = *styles.last().expect("The base style is never popped");
+        line.push(style, &rest[..i]);
+        rest = &rest[i..];
+
+        if rest.starts_with('}') || rest.starts_with("{{") {
+            // escaped braces are doubled, but a single closing brace is also just text
+            line.push(style, &rest[..1]);
+            let escaped = rest.starts_with("{{") || rest.starts_with("}}");
+            rest = &rest[if escaped { 2 } else { 1 }..];
+            continue;
+        }
+
+        let tag = rest[1..].find('}').map(|end| &rest[1..end + 1]);
+        match tag {
+            Some("/") => {
+                if styles.len() > 1 {
+                    styles.pop();
+                }
+            }
+            Some(tag) if tag_style(tag, style).is_some() => {
+                let style = tag_style(tag, style).expect("The tag was just checked");
+                styles.push(style);
+                if let Some(prefix) = tag_prefix(tag) {
+                    line.push(style, prefix);
+                }
+            }
+            _ => {
+                // not a tag, so the brace is just text
+                line.push(style, &rest[..1]);
+                rest = &rest[1..];
+                continue;
+            }
+        }
+        rest = &rest[tag.map_or(0, |t| t.len()) + 2..];
+    }
+
+    line.push(
+        *styles.last().expect("The base style is never popped"),
+        rest,
+    );
+    line.0.retain(|span| !span.content().is_empty());
+    line
+}
+
+/// Escape text so it is not parsed as markup
+pub fn escape(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::style::{Attribute, Stylize};
+
+    use super::*;
+
+    /// The text of a line, without styles
+    fn text(line: &Line) -> String {
+        line.0.iter().map(|s| s.content().as_str()).collect()
+    }
+
+    fn bold() -> ContentStyle {
+        ContentStyle::new().attribute(Attribute::Bold)
+    }
+
+    #[test]
+    fn plain_text_is_one_span() {
+        let line = parse("just text");
+        assert_eq!(line.0.len(), 1);
+        assert_eq!(text(&line), "just text");
+    }
+
+    #[test]
+    fn tags_style_text_up_to_their_close() {
+        let line = parse("a{bold}b{/}c");
+        assert_eq!(text(&line), "abc");
+        assert_eq!(*line.0[0].style(), ContentStyle::new());
+        assert_eq!(*line.0[1].style(), bold());
+        assert_eq!(*line.0[2].style(), ContentStyle::new());
+    }
+
+    #[test]
+    fn nested_tags_keep_the_outer_style() {
+        let line = parse("{bold}{italic}a{/}b{/}");
+        assert_eq!(text(&line), "ab");
+        assert_eq!(*line.0[0].style(), bold().attribute(Attribute::Italic));
+        assert_eq!(*line.0[1].style(), bold());
+    }
+
+    #[test]
+    fn unclosed_tags_style_the_rest() {
+        let line = parse("a{bold}bc");
+        assert_eq!(text(&line), "abc");
+        assert_eq!(*line.0[1].style(), bold());
+    }
+
+    #[test]
+    fn unknown_tags_are_kept_as_text() {
+        let line = parse("{nope}a{/}");
+        assert_eq!(text(&line), "{nope}a");
+        assert!(line.0.iter().all(|s| *s.style() == ContentStyle::new()));
+    }
+
+    #[test]
+    fn stray_braces_are_text() {
+        assert_eq!(text(&parse("a}b{/}c")), "a}bc");
+        assert_eq!(text(&parse("a{")), "a{");
+    }
+
+    #[test]
+    fn escaped_text_round_trips() {
+        for original in ["{bold}", "a {b} }c{", "{{}}", "{/}", "plain"] {
+            let line = parse(&escape(original));
+            assert_eq!(text(&line), original);
+            assert!(line.0.iter().all(|s| *s.style() == ContentStyle::new()));
+        }
+    }
+}
@@ -1,4 +1,11 @@
 pub mod commands;
+pub mod config;
 pub mod conv;
 pub mod flag;
+pub mod layout;
+pub mod list;
+pub mod markup;
+pub mod number;
+pub mod rng;
 pub mod style;
+pub mod text;
@@ -0,0 +1,509 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter, Result},
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Sub, SubAssign},
+    sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+};
+
+use enum_iterator::{all, next_cycle, Sequence};
+
+use crate::locale::t;
+
+/// Values at or above this are stored with an exponent
+const LIMIT: f64 = 1e300;
+/// The exponent is always a multiple of this
+const STEP: i64 = 100;
+/// The factor of one step of the exponent
+const SCALE: f64 = 1e100;
+/// The largest exponent, numbers above it are kept at the largest number instead of growing forever
+const MAX_EXPONENT: i64 = 1_000_000_000;
+
+/// Suffixes for every thousand, used by the suffix format
+const SUFFIXES: [&str; 12] = [
+    "", "K", "M", "B", "T", "Qa", "Qi", "Sx", "Sp", "Oc", "No", "Dc",
+];
+
+static FORMAT: AtomicUsize = AtomicUsize::new(0);
+
+/// A non-negative number that can grow far beyond the range of `f64`, for counts and currencies.
+/// Values below 1e300 are stored as a plain `f64`, so whole numbers stay exact for as long as `f64` allows.
+/// Subtraction stops at zero, and dividing by zero gives zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BigNum {
+    value: f64,
+    exponent: i64,
+}
+
+impl BigNum {
+    pub const ZERO: BigNum = BigNum {
+        value: 0.0,
+        exponent: 0,
+    };
+
+    /// The largest number, which infinite and too large results saturate to
+    const MAX: BigNum = BigNum {
+        value: LIMIT * 0.999,
+        exponent: MAX_EXPONENT,
+    };
+
+    /// Create a number from its base 10 logarithm
+    pub fn from_log10(log: f64) -> BigNum {
+        if log.is_nan() {
+            return BigNum::ZERO;
+        }
+        if log >= (MAX_EXPONENT + STEP) as f64 {
+            return BigNum::MAX;
+        }
+        // the exponent leaves a value in [1e200, 1e300), which always fits in an `f64`
+        let exponent = (((log - 200.0) / STEP as f64).floor() as i64).max(0) * STEP;
+        BigNum {
+            value: 10f64.powf(log - exponent as f64),
+            exponent,
+        }
+        .normalize()
+    }
+
+    /// The base 10 logarithm of this number
+    pub fn log10(&self) -> f64 {
+        self.value.log10() + self.exponent as f64
+    }
+
+    /// Raise this number to a power
+    pub fn pow(&self, power: f64) -> BigNum {
+        if self.value == 0.0 {
+            return BigNum::ZERO;
+        }
+        BigNum::from_log10(self.log10() * power)
+    }
+
+    /// Round this number down to a whole number
+    pub fn floor(&self) -> BigNum {
+        BigNum {
+            value: self.value.floor(),
+            exponent: self.exponent,
+        }
+    }
+
+    /// Get this number as an `f64`, which is infinite if it is too large
+    pub fn to_f64(self) -> f64 {
+        self.value * 10f64.powi(self.exponent as i32)
+    }
+
+    /// Format this number in a format.
+    /// Numbers below 1000 are shown as they are, with a number of decimals.
+    pub fn format(&self, format: NumberFormat, decimals: usize) -> String {
+        if self.exponent == 0 && self.value < 1000.0 {
+            return truncate(self.value, decimals);
+        }
+
+        // split into a mantissa in [1, 10) and an exponent
+        let (mut mantissa, mut exponent) = if self.exponent == 0 {
+            let exponent = self.value.log10().floor() as i64;
+            (self.value / 10f64.powi(exponent as i32), exponent)
+        } else {
+            let log = self.log10();
+            (10f64.powf(log - log.floor()), log.floor() as i64)
+        };
+        // the logarithm can be off by a tiny bit around powers of 10
+        if mantissa >= 10.0 - 1e-9 {
+            mantissa /= 10.0;
+            exponent += 1;
+        } else if mantissa < 1.0 - 1e-9 {
+            mantissa *= 10.0;
+            exponent -= 1;
+        }
+
+        let group = exponent - exponent % 3;
+        match format {
+            NumberFormat::Suffix if ((group / 3) as usize) < SUFFIXES.len() => {
+                let shown = mantissa * 10f64.powi((exponent - group) as i32);
+                format!(
+                    "{}{}",
+                    truncate(shown, 2 - (exponent - group) as usize),
+                    SUFFIXES[(group / 3) as usize]
+                )
+            }
+            NumberFormat::Engineering => {
+                let shown = mantissa * 10f64.powi((exponent - group) as i32);
+                format!(
+                    "{}e{}",
+                    truncate(shown, 2 - (exponent - group) as usize),
+                    group
+                )
+            }
+            _ => format!("{}e{}", truncate(mantissa, 2), exponent),
+        }
+    }
+
+    /// Keep the value in range, and the exponent a multiple of the step.
+    /// Infinite values saturate to the largest number, so they can never get stuck scaling down.
+    fn normalize(mut self) -> BigNum {
+        if self.value.is_nan() || self.value <= 0.0 {
+            return BigNum::ZERO;
+        }
+        if self.value.is_infinite() {
+            return BigNum::MAX;
+        }
+        while self.value >= LIMIT {
+            self.value /= SCALE;
+            self.exponent += STEP;
+        }
+        while self.exponent > 0 && self.value < LIMIT / SCALE {
+            self.value *= SCALE;
+            self.exponent -= STEP;
+        }
+        while self.exponent < 0 {
+            self.value /= SCALE;
+            self.exponent += STEP;
+            if self.value == 0.0 {
+                return BigNum::ZERO;
+            }
+        }
+        if self.exponent > MAX_EXPONENT {
+            return BigNum::MAX;
+        }
+        self
+    }
+
+    /// The value of this number, scaled to a larger exponent
+    fn scaled(&self, exponent: i64) -> f64 {
+        self.value * 10f64.powi((self.exponent - exponent) as i32)
+    }
+}
+
+/// Cut a number off to a number of decimals, so it is never shown as more than it is
+fn truncate(value: f64, decimals: usize) -> String {
+    let factor = 10f64.powi(decimals as i32);
+    // a small margin keeps values like 1.23 from being shown as 1.22
+    let value = (value * factor + 1e-6).floor() / factor;
+    format!("{:.*}", decimals, value)
+}
+
+impl From<f64> for BigNum {
+    fn from(value: f64) -> Self {
+        BigNum { value, exponent: 0 }.normalize()
+    }
+}
+
+impl From<u64> for BigNum {
+    fn from(value: u64) -> Self {
+        BigNum::from(value as f64)
+    }
+}
+
+impl From<u32> for BigNum {
+    fn from(value: u32) -> Self {
+        BigNum::from(value as f64)
+    }
+}
+
+impl Add for BigNum {
+    type Output = BigNum;
+
+    fn add(self, other: BigNum) -> BigNum {
+        let exponent = self.exponent.max(other.exponent);
+        BigNum {
+            value: self.scaled(exponent) + other.scaled(exponent),
+            exponent,
+        }
+        .normalize()
+    }
+}
+
+impl Sub for BigNum {
+    type Output = BigNum;
+
+    fn sub(self, other: BigNum) -> BigNum {
+        let exponent = self.exponent.max(other.exponent);
+        BigNum {
+            value: self.scaled(exponent) - other.scaled(exponent),
+            exponent,
+        }
+        .normalize()
+    }
+}
+
+impl Mul for BigNum {
+    type Output = BigNum;
+
+    fn mul(self, other: BigNum) -> BigNum {
+        let value = self.value * other.value;
+        if value.is_finite() {
+            BigNum {
+                value,
+                exponent: self.exponent + other.exponent,
+            }
+        } else {
+            BigNum {
+                value: (self.value / 1e200) * (other.value / 1e200),
+                exponent: self.exponent + other.exponent + 400,
+            }
+        }
+        .normalize()
+    }
+}
+
+impl Div for BigNum {
+    type Output = BigNum;
+
+    fn div(self, other: BigNum) -> BigNum {
+        if other.value == 0.0 {
+            return BigNum::ZERO;
+        }
+        let value = self.value / other.value;
+        if value.is_finite() {
+            BigNum {
+                value,
+                exponent: self.exponent - other.exponent,
+            }
+        } else {
+            BigNum {
+                value: (self.value / 1e200) / other.value,
+                exponent: self.exponent - other.exponent + 200,
+            }
+        }
+        .normalize()
+    }
+}
+
+impl Mul<f64> for BigNum {
+    type Output = BigNum;
+
+    fn mul(self, other: f64) -> BigNum {
+        self * BigNum::from(other)
+    }
+}
+
+impl Div<f64> for BigNum {
+    type Output = BigNum;
+
+    fn div(self, other: f64) -> BigNum {
+        self / BigNum::from(other)
+    }
+}
+
+impl<T: Into<BigNum>> AddAssign<T> for BigNum {
+    fn add_assign(&mut self, other: T) {
+        *self = *self + other.into();
+    }
+}
+
+impl<T: Into<BigNum>> SubAssign<T> for BigNum {
+    fn sub_assign(&mut self, other: T) {
+        *self = *self - other.into();
+    }
+}
+
+impl MulAssign<f64> for BigNum {
+    fn mul_assign(&mut self, other: f64) {
+        *self = *self * other;
+    }
+}
+
+impl PartialOrd for BigNum {
+    fn partial_cmp(&self, other: &BigNum) -> Option<Ordering> {
+        // numbers are normalized, so a larger exponent always means a larger number
+        match self.exponent.cmp(&other.exponent) {
+            Ordering::Equal => self.value.partial_cmp(&other.value),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl PartialEq<u64> for BigNum {
+    fn eq(&self, other: &u64) -> bool {
+        *self == BigNum::from(*other)
+    }
+}
+
+impl PartialOrd<u64> for BigNum {
+    fn partial_cmp(&self, other: &u64) -> Option<Ordering> {
+        self.partial_cmp(&BigNum::from(*other))
+    }
+}
+
+/// Formats in the selected number format.
+/// The precision sets the decimals of numbers below 1000, like `{:.1}`, and is 0 by default.
+impl Display for BigNum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.format(format(), f.precision().unwrap_or(0)))
+    }
+}
+
+/// The ways large numbers can be shown
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum NumberFormat {
+    /// Like 1.23K or 4.56M
+    Suffix,
+    /// Like 1.23e45
+    Scientific,
+    /// Like 12.3e6, with the exponent a multiple of 3
+    Engineering,
+}
+
+impl NumberFormat {
+    /// The name of this format, as used in the settings
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumberFormat::Suffix => "suffix",
+            NumberFormat::Scientific => "scientific",
+            NumberFormat::Engineering => "engineering",
+        }
+    }
+
+    /// The translated name of this format
+    pub fn label(&self) -> &'static str {
+        match self {
+            NumberFormat::Suffix => t("number.format.suffix"),
+            NumberFormat::Scientific => t("number.format.scientific"),
+            NumberFormat::Engineering => t("number.format.engineering"),
+        }
+    }
+}
+
+/// Select the number format by its name, like `scientific`
+pub fn init(name: &str) {
+    if let Some(format) = all::<NumberFormat>().find(|f| f.name() == name) {
+        set_format(format);
+    }
+}
+
+/// Get the selected number format
+pub fn format() -> NumberFormat {
+    all::<NumberFormat>()
+        .nth(FORMAT.load(AtomicOrdering::Relaxed))
+        .unwrap_or(NumberFormat::Suffix)
+}
+
+/// Select the next number format
+pub fn next_format() -> NumberFormat {
+    let format = next_cycle(&format()).unwrap_or(NumberFormat::Suffix);
+    set_format(format);
+    format
+}
+
+fn set_format(format: NumberFormat) {
+    let index = all::<NumberFormat>().position(|f| f == format).unwrap_or(0);
+    FORMAT.store(index, AtomicOrdering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check that two numbers are equal up to rounding
+    fn assert_close(a: BigNum, b: BigNum) {
+        assert!(
+            (a.log10() - b.log10()).abs() < 1e-9,
+            "{:?} is not close to {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn from_log10_gives_finite_values_across_the_exponent_boundary() {
+        for log in [
+            0.0, 2.5, 250.0, 299.9, 300.0, 308.25, 308.5, 320.0, 350.0, 1000.0, 1e6,
+        ] {
+            let n = BigNum::from_log10(log);
+            assert!(
+                n.value.is_finite() && n.value < LIMIT,
+                "{} gave {:?}",
+                log,
+                n
+            );
+            assert!((n.log10() - log).abs() < 1e-6, "{} gave {:?}", log, n);
+        }
+    }
+
+    #[test]
+    fn huge_and_infinite_values_saturate() {
+        assert_eq!(BigNum::from(f64::INFINITY), BigNum::MAX);
+        assert_eq!(BigNum::from_log10(f64::INFINITY), BigNum::MAX);
+        assert_eq!(BigNum::from_log10(f64::NAN), BigNum::ZERO);
+        assert_eq!(BigNum::MAX * BigNum::MAX, BigNum::MAX);
+        assert_eq!(BigNum::from(2.0).pow(1e12), BigNum::MAX);
+    }
+
+    #[test]
+    fn pow_matches_f64_and_goes_beyond_it() {
+        assert_close(BigNum::from(2.0).pow(10.0), BigNum::from(1024.0));
+        assert_eq!(BigNum::ZERO.pow(3.0), BigNum::ZERO);
+        // 2^1100 and 1.15^5000 are far beyond the range of an `f64`
+        assert_close(
+            BigNum::from(2.0).pow(1100.0),
+            BigNum::from_log10(1100.0 * 2f64.log10()),
+        );
+        assert_close(
+            BigNum::from(1.15).pow(5000.0),
+            BigNum::from_log10(5000.0 * 1.15f64.log10()),
+        );
+    }
+
+    #[test]
+    fn add_and_sub_across_the_exponent_boundary() {
+        let big = BigNum::from(9e299);
+        let sum = big + big;
+        assert_eq!(sum.exponent, STEP);
+        assert_close(sum, BigNum::from_log10(1.8e300f64.log10()));
+        assert_close(sum - big, big);
+
+        assert_eq!(BigNum::from(1.0) + BigNum::from(2.0), BigNum::from(3.0));
+        assert_eq!(BigNum::from(1.0) - BigNum::from(2.0), BigNum::ZERO);
+        assert_eq!(sum - sum, BigNum::ZERO);
+    }
+
+    #[test]
+    fn mul_and_div() {
+        assert_eq!(BigNum::from(6.0) / BigNum::from(3.0), BigNum::from(2.0));
+        assert_eq!(BigNum::from(6.0) / BigNum::ZERO, BigNum::ZERO);
+        let big = BigNum::from_log10(400.0);
+        assert_close(big * big, BigNum::from_log10(800.0));
+        assert_close((big * big) / big, big);
+    }
+
+    #[test]
+    fn cmp_across_the_exponent_boundary() {
+        let below = BigNum::from(9e299);
+        let above = BigNum::from_log10(301.0);
+        assert!(above > below);
+        assert!(below < above);
+        assert!(BigNum::from_log10(400.0) > BigNum::from_log10(399.0));
+        assert!(BigNum::from(5.0) > 4u64);
+        assert_eq!(BigNum::from(5.0), 5u64);
+    }
+
+    #[test]
+    fn format_small_numbers() {
+        assert_eq!(BigNum::from(12.345).format(NumberFormat::Suffix, 1), "12.3");
+        assert_eq!(
+            BigNum::from(999.0).format(NumberFormat::Scientific, 0),
+            "999"
+        );
+    }
+
+    #[test]
+    fn format_large_numbers() {
+        let n = BigNum::from(1234.0);
+        assert_eq!(n.format(NumberFormat::Suffix, 0), "1.23K");
+        assert_eq!(n.format(NumberFormat::Scientific, 0), "1.23e3");
+        assert_eq!(
+            BigNum::from(12345678.0).format(NumberFormat::Engineering, 0),
+            "12.3e6"
+        );
+    }
+
+    #[test]
+    fn format_across_the_exponent_boundary() {
+        for log in [299.0, 300.0, 301.0, 320.0, 1000.0] {
+            let n = BigNum::from_log10(log);
+            assert_eq!(
+                n.format(NumberFormat::Scientific, 0),
+                format!("1.00e{}", log)
+            );
+        }
+        let n = BigNum::from(2.5) * BigNum::from_log10(300.0);
+        assert_eq!(n.format(NumberFormat::Engineering, 0), "2.50e300");
+    }
+}
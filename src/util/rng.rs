@@ -0,0 +1,199 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use enum_iterator::{all, Sequence};
+
+/// A small, fast random number generator (xorshift64*).
+/// It is not suitable for anything secure, but the same seed always gives the same numbers.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // the state can never be zero, or it would stay zero
+        Self {
+            state: (seed ^ 0x9E37_79B9_7F4A_7C15).max(1),
+        }
+    }
+
+    /// Continue a generator from a state given by `state`
+    pub fn from_state(state: u64) -> Self {
+        Self {
+            state: state.max(1),
+        }
+    }
+
+    /// The current state of the generator, to continue it later with `from_state`
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Get a number in the range [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Pick an index with a chance proportional to its weight.
+    /// Returns `None` if there are no weights, or they are all zero.
+    pub fn pick_weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        let total: f64 = weights.iter().filter(|w| **w > 0.0).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = self.next_f64() * total;
+        for (i, weight) in weights.iter().enumerate() {
+            if *weight <= 0.0 {
+                continue;
+            }
+            if roll < *weight {
+                return Some(i);
+            }
+            roll -= weight;
+        }
+        weights.iter().rposition(|w| *w > 0.0)
+    }
+}
+
+/// The systems that roll random numbers, each with its own stream
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Sequence)]
+pub enum Stream {
+    /// Enemy spawns and everything rolled during a fight
+    Combat,
+    /// Items dropped by enemies
+    Loot,
+    /// The stock of the shop
+    Shop,
+}
+
+impl Stream {
+    /// The name of the stream, as used in the save file
+    pub fn name(self) -> &'static str {
+        match self {
+            Stream::Combat => "combat",
+            Stream::Loot => "loot",
+            Stream::Shop => "shop",
+        }
+    }
+}
+
+/// The random numbers of the game.
+/// A single seed is split into an independent generator per stream,
+/// so rolling more in one system never changes the numbers of another, and the same seed plays out the same.
+#[derive(Debug, Clone)]
+pub struct Random {
+    seed: u64,
+    streams: Vec<Rng>,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: all::<Stream>()
+                .map(|stream| Rng::new(mix(seed ^ mix(stream as u64 + 1))))
+                .collect(),
+        }
+    }
+
+    /// Create random numbers seeded with the current time
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self::new(nanos)
+    }
+
+    /// The seed these random numbers started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get the generator of a stream
+    pub fn stream(&mut self, stream: Stream) -> &mut Rng {
+        &mut self.streams[stream as usize]
+    }
+
+    /// The state of the generator of a stream, to save it
+    pub fn state(&self, stream: Stream) -> u64 {
+        self.streams[stream as usize].state()
+    }
+}
+
+/// Scramble a number (splitmix64), so seeds that are close together give unrelated streams
+fn mix(x: u64) -> u64 {
+    let mut x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The next few numbers of a stream
+    fn roll(random: &mut Random, stream: Stream) -> Vec<u64> {
+        (0..5).map(|_| random.stream(stream).next_u64()).collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_numbers() {
+        for stream in all::<Stream>() {
+            assert_eq!(
+                roll(&mut Random::new(42), stream),
+                roll(&mut Random::new(42), stream)
+            );
+        }
+        assert_ne!(
+            roll(&mut Random::new(42), Stream::Combat),
+            roll(&mut Random::new(43), Stream::Combat)
+        );
+    }
+
+    #[test]
+    fn streams_are_independent() {
+        let mut quiet = Random::new(7);
+        let mut busy = Random::new(7);
+        roll(&mut busy, Stream::Combat);
+        roll(&mut busy, Stream::Shop);
+
+        assert_eq!(
+            roll(&mut quiet, Stream::Loot),
+            roll(&mut busy, Stream::Loot)
+        );
+        assert_ne!(
+            roll(&mut Random::new(7), Stream::Combat),
+            roll(&mut Random::new(7), Stream::Loot)
+        );
+    }
+
+    #[test]
+    fn a_saved_state_continues_the_stream() {
+        let mut random = Random::new(3);
+        roll(&mut random, Stream::Loot);
+        let mut continued = Rng::from_state(random.state(Stream::Loot));
+
+        let expected = roll(&mut random, Stream::Loot);
+        let numbers: Vec<u64> = (0..5).map(|_| continued.next_u64()).collect();
+        assert_eq!(numbers, expected);
+    }
+
+    #[test]
+    fn weighted_picks_skip_empty_weights() {
+        let mut rng = Rng::new(1);
+        assert_eq!(rng.pick_weighted(&[]), None);
+        assert_eq!(rng.pick_weighted(&[0.0, -1.0]), None);
+        for _ in 0..100 {
+            assert_eq!(rng.pick_weighted(&[0.0, 2.0, 0.0]), Some(1));
+        }
+    }
+}
@@ -1,16 +1,88 @@
-use crossterm::style::Stylize;
-
-use crate::feature::inventory::Rarity;
-
-/// Stylize a string based on its rarity
-pub fn rarity_stylize(rarity: Rarity, string: &str) -> String {
-    match rarity {
-        Rarity::Common => string.stylize().white(),
-        Rarity::Uncommon => string.stylize().green(),
-        Rarity::Rare => string.stylize().blue(),
-        Rarity::Epic => string.stylize().magenta(),
-        Rarity::Legendary => string.stylize().yellow(),
-        Rarity::Mythic => string.stylize().red().bold(),
+use crossterm::style::{Attribute, Color, ContentStyle, Stylize};
+
+use crate::{
+    feature::inventory::Rarity,
+    theme::{theme, Role},
+};
+
+/// Get the markup tag of a rarity, like `rarity:rare`
+pub fn rarity_tag(rarity: Rarity) -> String {
+    format!("rarity:{}", format!("{:?}", rarity).to_lowercase())
+}
+
+/// Get the rarity of a markup tag like `rarity:rare`
+fn tag_rarity(tag: &str) -> Option<Rarity> {
+    Rarity::from_name(tag.strip_prefix("rarity:")?)
+}
+
+/// Apply a markup tag on top of a style.
+/// Tags are either a semantic role (`gold`, `danger`, `rarity:epic`), a color, or an attribute.
+/// Roles are styled by the selected theme, and colors are ignored if the theme has none.
+/// Returns `None` if the tag is unknown.
+pub fn tag_style(tag: &str, base: ContentStyle) -> Option<ContentStyle> {
+    if let Some(rarity) = tag_rarity(tag) {
+        return Some(merge(base, theme().style(Role::Rarity(rarity))));
+    }
+
+    Some(match tag {
+        // semantic tags
+        "title" => merge(base, theme().style(Role::Title)),
+        "gold" => merge(base, theme().style(Role::Gold)),
+        "danger" => merge(base, theme().style(Role::Danger)),
+        "success" => merge(base, theme().style(Role::Success)),
+        "info" => merge(base, theme().style(Role::Info)),
+        "dim" => merge(base, theme().style(Role::Dim)),
+        // attributes
+        "bold" => base.attribute(Attribute::Bold),
+        "italic" => base.attribute(Attribute::Italic),
+        "underline" => base.attribute(Attribute::Underlined),
+        "crossed" => base.attribute(Attribute::CrossedOut),
+        // colors
+        color => {
+            let color = parse_color(color)?;
+            if theme().has_colors() {
+                base.with(color)
+            } else {
+                base
+            }
+        }
+    })
+}
+
+/// Get the text shown at the start of a markup tag, like the rarity symbols of some themes
+pub fn tag_prefix(tag: &str) -> Option<&'static str> {
+    tag_rarity(tag).and_then(|rarity| theme().symbol(rarity))
+}
+
+/// Merge a style on top of another, keeping the attributes of both
+fn merge(base: ContentStyle, top: ContentStyle) -> ContentStyle {
+    ContentStyle {
+        foreground_color: top.foreground_color.or(base.foreground_color),
+        background_color: top.background_color.or(base.background_color),
+        underline_color: top.underline_color.or(base.underline_color),
+        attributes: base.attributes | top.attributes,
     }
-    .to_string()
+}
+
+/// Parse the name of a color
+fn parse_color(name: &str) -> Option<Color> {
+    Some(match name {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "grey" => Color::Grey,
+        "dark_red" => Color::DarkRed,
+        "dark_green" => Color::DarkGreen,
+        "dark_yellow" => Color::DarkYellow,
+        "dark_blue" => Color::DarkBlue,
+        "dark_magenta" => Color::DarkMagenta,
+        "dark_cyan" => Color::DarkCyan,
+        "dark_grey" => Color::DarkGrey,
+        _ => return None,
+    })
 }
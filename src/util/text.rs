@@ -0,0 +1,276 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crossterm::style::{ContentStyle, StyledContent};
+use unicode_width::UnicodeWidthChar;
+
+/// A line of styled spans.
+/// Unlike a formatted string, every span keeps its own style, so a line can be split without losing styling.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Line(pub Vec<StyledContent<String>>);
+
+impl Line {
+    /// The display width of this line in terminal columns
+    pub fn width(&self) -> usize {
+        self.0
+            .iter()
+            .map(|span| display_width(span.content()))
+            .sum()
+    }
+
+    /// Add text to the end of this line, merging it with the last span if the style is the same
+    pub fn push(&mut self, style: ContentStyle, text: &str) {
+        match self.0.last_mut() {
+            Some(last) if *last.style() == style => {
+                *last = StyledContent::new(style, format!("{}{}", last.content(), text));
+            }
+            _ => self.0.push(StyledContent::new(style, text.to_string())),
+        }
+    }
+
+    /// Cut this line off or pad it with spaces, so it is exactly `width` columns wide
+    pub fn fit(&self, width: usize) -> Line {
+        let mut line = Line::default();
+        let mut len = 0;
+        'spans: for span in &self.0 {
+            for piece in pieces(span.content()) {
+                let w = piece_width(piece);
+                if len + w > width {
+                    break 'spans;
+                }
+                line.push(*span.style(), piece);
+                len += w;
+            }
+        }
+        line.push(ContentStyle::new(), &" ".repeat(width - len));
+        line
+    }
+
+    /// Add all spans of another line to the end of this line
+    pub fn append(&mut self, other: Line) {
+        other
+            .0
+            .iter()
+            .for_each(|span| self.push(*span.style(), span.content()));
+    }
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.0.iter().try_for_each(|span| write!(f, "{}", span))
+    }
+}
+
+impl From<StyledContent<String>> for Line {
+    fn from(span: StyledContent<String>) -> Self {
+        Line(vec![span])
+    }
+}
+
+/// Split a string into pieces: escape sequences as a whole, and every other character on its own.
+/// This keeps escape codes baked into a string from being split or counted as text.
+pub fn pieces(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let mut chars = rest.char_indices();
+        let (_, first) = chars.next()?;
+
+        let end = if first == '\x1b' && rest[1..].starts_with('[') {
+            // a CSI sequence ends with a byte in the range @ to ~
+            rest[2..]
+                .find(|c: char| ('@'..='~').contains(&c))
+                .map_or(rest.len(), |i| i + 3)
+        } else {
+            first.len_utf8()
+        };
+
+        let (piece, tail) = rest.split_at(end);
+        rest = tail;
+        Some(piece)
+    })
+}
+
+/// The display width of a piece of text, ignoring escape sequences and counting wide characters double
+pub fn display_width(text: &str) -> usize {
+    pieces(text).map(piece_width).sum()
+}
+
+/// The display width of a single piece
+fn piece_width(piece: &str) -> usize {
+    match piece.chars().next() {
+        Some('\x1b') | None => 0,
+        Some(c) => c.width().unwrap_or(0),
+    }
+}
+
+/// Builds wrapped lines out of words and the whitespace between them
+pub struct Wrapper {
+    width: usize,
+    lines: Vec<(u16, Line)>,
+    line: Line,
+    len: usize,
+    space: Line,
+    word: Line,
+    word_len: usize,
+}
+
+impl Wrapper {
+    pub fn new(width: u16) -> Self {
+        Self {
+            width: (width as usize).max(1),
+            lines: Vec::new(),
+            line: Line::default(),
+            len: 0,
+            space: Line::default(),
+            word: Line::default(),
+            word_len: 0,
+        }
+    }
+
+    /// Add a styled span of text
+    pub fn add(&mut self, span: &StyledContent<String>) {
+        let style = *span.style();
+        for piece in pieces(span.content()) {
+            if piece == "\n" {
+                self.flush_word();
+                self.break_line();
+            } else if piece.chars().all(char::is_whitespace) && !piece.is_empty() {
+                self.flush_word();
+                self.space.push(style, piece);
+            } else {
+                self.word.push(style, piece);
+                self.word_len += piece_width(piece);
+            }
+        }
+    }
+
+    /// Finish wrapping, returning all lines with their display widths
+    pub fn finish(mut self) -> Vec<(u16, Line)> {
+        self.flush_word();
+        self.break_line();
+        self.lines
+    }
+
+    /// Place the current word on the current line, or on a new one if it does not fit
+    fn flush_word(&mut self) {
+        if self.word.0.is_empty() {
+            return;
+        }
+
+        let space = std::mem::take(&mut self.space);
+        let space_len = space.width();
+        if self.len > 0 && self.len + space_len + self.word_len > self.width {
+            self.break_line();
+        } else {
+            space
+                .0
+                .iter()
+                .for_each(|s| self.line.push(*s.style(), s.content()));
+            self.len += space_len;
+        }
+
+        let word = std::mem::take(&mut self.word);
+        if self.word_len <= self.width.saturating_sub(self.len) {
+            word.0
+                .iter()
+                .for_each(|s| self.line.push(*s.style(), s.content()));
+            self.len += self.word_len;
+        } else {
+            // the word is too long for any line, so it is broken up
+            for span in word.0 {
+                for piece in pieces(span.content()) {
+                    let w = piece_width(piece);
+                    if self.len > 0 && self.len + w > self.width {
+                        self.break_line();
+                    }
+                    self.line.push(*span.style(), piece);
+                    self.len += w;
+                }
+            }
+        }
+        self.word_len = 0;
+    }
+
+    /// End the current line and start a new one
+    fn break_line(&mut self) {
+        self.space = Line::default();
+        self.lines
+            .push((self.len as u16, std::mem::take(&mut self.line)));
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::style::Stylize;
+
+    use super::*;
+
+    /// Wrap a line and give the text of every wrapped line
+    fn wrap_text(line: &Line, width: u16) -> Vec<String> {
+        let mut wrapper = Wrapper::new(width);
+        line.0.iter().for_each(|span| wrapper.add(span));
+        wrapper
+            .finish()
+            .iter()
+            .map(|(_, line)| line.0.iter().map(|s| s.content().as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn fit_pads_short_lines() {
+        let line = Line::from("ab".to_string().red()).fit(4);
+        assert_eq!(line.width(), 4);
+        assert_eq!(line.0[0], "ab".to_string().red());
+    }
+
+    #[test]
+    fn fit_stops_at_the_cut_across_spans() {
+        // the second span is cut off, and the narrow third span must not be appended after it
+        let line = Line(vec![
+            "abc".to_string().red(),
+            "你好".to_string().blue(),
+            "d".to_string().green(),
+        ])
+        .fit(4);
+        assert_eq!(line.width(), 4);
+        assert_eq!(line.0[0], "abc".to_string().red());
+        assert!(line.0.iter().all(|s| !s.content().contains('d')));
+    }
+
+    #[test]
+    fn display_width_counts_wide_characters_double() {
+        assert_eq!(display_width("a你b"), 4);
+        assert_eq!(display_width("\x1b[31mab\x1b[0m"), 2);
+    }
+
+    #[test]
+    fn wrap_breaks_between_words() {
+        let line = Line::from("one two three".to_string().stylize());
+        assert_eq!(wrap_text(&line, 7), vec!["one two", "three"]);
+    }
+
+    #[test]
+    fn wrap_counts_wide_characters() {
+        let line = Line::from("你好 世界".to_string().stylize());
+        assert_eq!(wrap_text(&line, 4), vec!["你好", "世界"]);
+        assert_eq!(wrap_text(&line, 3), vec!["你", "好", "世", "界"]);
+    }
+
+    #[test]
+    fn wrap_breaks_up_long_words() {
+        let line = Line::from("abcdefgh".to_string().stylize());
+        assert_eq!(wrap_text(&line, 3), vec!["abc", "def", "gh"]);
+    }
+
+    #[test]
+    fn wrap_keeps_styles_across_breaks() {
+        let line = Line(vec!["aa ".to_string().stylize(), "bbbb".to_string().red()]);
+        let mut wrapper = Wrapper::new(3);
+        line.0.iter().for_each(|span| wrapper.add(span));
+        let lines = wrapper.finish();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].1 .0, vec!["bbb".to_string().red()]);
+        assert_eq!(lines[2].1 .0, vec!["b".to_string().red()]);
+    }
+}